@@ -0,0 +1,66 @@
+//! Per-tick delta coalescing over a delta-state manager
+
+use std::ops::BitXor;
+
+use crate::manager::DeltaManager;
+
+/// Wrapper that folds every delta pushed within a tick into one
+///
+/// Deltas are XORed into a pending value as they arrive; `flush_tick`
+/// hands that single combined delta back for transport and accumulates
+/// it into the manager as one history entry.
+#[derive(Debug, Clone, Default)]
+pub struct TickBatcher<M: DeltaManager> {
+    inner: M,
+    pending: M::State,
+    pending_count: usize,
+}
+
+impl<M> TickBatcher<M>
+where
+    M: DeltaManager,
+    M::State: Default + BitXor<Output = M::State>,
+{
+    /// Wrap a manager with an empty tick
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            pending: M::State::default(),
+            pending_count: 0,
+        }
+    }
+
+    /// Add a delta to the current tick
+    pub fn push(&mut self, delta: M::State) {
+        self.pending = self.pending ^ delta;
+        self.pending_count += 1;
+    }
+
+    /// Close the current tick, returning the XOR of its deltas
+    ///
+    /// The combined delta is accumulated into the manager as a single
+    /// entry. A tick with no pushed deltas returns zero and leaves the
+    /// manager untouched.
+    pub fn flush_tick(&mut self) -> M::State {
+        let combined = std::mem::take(&mut self.pending);
+        if std::mem::take(&mut self.pending_count) > 0 {
+            self.inner.accumulate(combined);
+        }
+        combined
+    }
+
+    /// Get the number of deltas pushed since the last flush
+    pub fn pending_count(&self) -> usize {
+        self.pending_count
+    }
+
+    /// Get the wrapped manager
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Unwrap the manager, discarding any unflushed deltas
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
@@ -0,0 +1,132 @@
+//! Delta state with independent rollback per bit field
+
+use std::collections::VecDeque;
+
+use crate::error::DeltaError;
+
+/// Delta-state manager keeping a separate history per declared bit field
+///
+/// Each accumulated delta is split by field mask and the non-zero parts
+/// are recorded in their field's own history, so one field can be rolled
+/// back without disturbing the others. The accumulator always reflects
+/// every field. Bits outside all declared fields are accumulated but not
+/// tracked, so no rollback undoes them.
+#[derive(Debug, Clone)]
+pub struct BitPlaneDeltaState {
+    /// Initial state
+    initial_state: u64,
+    /// Delta accumulator (XOR of all deltas)
+    accumulator: u64,
+    /// Bit mask of each declared field
+    masks: Vec<u64>,
+    /// Masked delta history per field, oldest first
+    histories: Vec<VecDeque<u64>>,
+    /// Maximum history depth per field
+    max_history: usize,
+}
+
+impl BitPlaneDeltaState {
+    /// Create a manager over fields given as `[lo, hi)` bit ranges
+    ///
+    /// Returns `InvalidBitRange` for an empty or out-of-width range and
+    /// `OverlappingFields` if two ranges share a bit.
+    pub fn new(fields: &[(u32, u32)]) -> Result<Self, DeltaError> {
+        let mut masks: Vec<u64> = Vec::with_capacity(fields.len());
+        for (index, &(lo, hi)) in fields.iter().enumerate() {
+            if lo >= hi || hi > u64::BITS {
+                return Err(DeltaError::InvalidBitRange { lo, hi, width: u64::BITS });
+            }
+            let mask = (u64::MAX >> (u64::BITS - (hi - lo))) << lo;
+            if let Some(first) = masks.iter().position(|earlier| earlier & mask != 0) {
+                return Err(DeltaError::OverlappingFields { first, second: index });
+            }
+            masks.push(mask);
+        }
+        Ok(Self {
+            initial_state: 0,
+            accumulator: 0,
+            histories: vec![VecDeque::new(); masks.len()],
+            masks,
+            max_history: 4096,
+        })
+    }
+
+    /// Load initial state (LOAD operation)
+    pub fn load(&mut self, initial_state: u64) {
+        self.initial_state = initial_state;
+        self.accumulator = 0;
+        for history in &mut self.histories {
+            history.clear();
+        }
+    }
+
+    /// Accumulate delta (ACCUMULATE operation)
+    ///
+    /// Records the delta's bits within each field in that field's history.
+    pub fn accumulate(&mut self, delta: u64) {
+        for (mask, history) in self.masks.iter().zip(&mut self.histories) {
+            let part = delta & mask;
+            if part != 0 {
+                history.push_back(part);
+                if history.len() > self.max_history {
+                    history.pop_front();
+                }
+            }
+        }
+        self.accumulator ^= delta;
+    }
+
+    /// Reconstruct current state (READ operation)
+    pub fn reconstruct(&self) -> u64 {
+        self.initial_state ^ self.accumulator
+    }
+
+    /// Rollback the last `count` deltas that touched field `field_index`
+    ///
+    /// Other fields keep their state and history. Returns the number of
+    /// deltas actually rolled back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `field_index` is not a declared field. With the `strict`
+    /// feature, also panics if `count` exceeds that field's history
+    /// instead of clamping.
+    pub fn rollback_field(&mut self, field_index: usize, count: usize) -> usize {
+        let history = &mut self.histories[field_index];
+        if cfg!(feature = "strict") {
+            assert!(
+                count <= history.len(),
+                "BitPlaneDeltaState rollback of {} exceeds field {} history of {}",
+                count,
+                field_index,
+                history.len()
+            );
+        }
+        let actual_count = count.min(history.len());
+        for _ in 0..actual_count {
+            if let Some(part) = history.pop_back() {
+                self.accumulator ^= part;
+            }
+        }
+        actual_count
+    }
+
+    /// Get the number of declared fields
+    pub fn field_count(&self) -> usize {
+        self.masks.len()
+    }
+
+    /// Get the number of deltas in one field's history
+    ///
+    /// # Panics
+    ///
+    /// Panics if `field_index` is not a declared field.
+    pub fn field_history_size(&self, field_index: usize) -> usize {
+        self.histories[field_index].len()
+    }
+
+    /// Get the current accumulator value
+    pub fn get_accumulator(&self) -> u64 {
+        self.accumulator
+    }
+}
@@ -0,0 +1,37 @@
+//! Invariant checking for accumulate / rollback sequences
+
+use std::fmt;
+
+/// One step of a sequence fed to a manager's `check_sequence`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op<S> {
+    /// Load initial state
+    Load(S),
+    /// Accumulate a delta
+    Accumulate(S),
+    /// Roll back this many deltas
+    Rollback(usize),
+}
+
+/// Manager state diverged from the reference model during `check_sequence`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceMismatch<S> {
+    /// Index of the op after which the states differed
+    pub index: usize,
+    /// State according to the reference model
+    pub expected: S,
+    /// State reconstructed by the manager
+    pub actual: S,
+}
+
+impl<S: fmt::LowerHex> fmt::Display for SequenceMismatch<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "op {} left state {:#x}, model expected {:#x}",
+            self.index, self.actual, self.expected
+        )
+    }
+}
+
+impl<S: fmt::Debug + fmt::LowerHex> std::error::Error for SequenceMismatch<S> {}
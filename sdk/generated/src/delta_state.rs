@@ -0,0 +1,1533 @@
+//! Shared implementation of the delta-state managers
+//!
+//! `PriceTick`, `IMUFusion` and `H264Delta` are all expansions of
+//! `delta_state_manager!`; they differ only in their names, state word
+//! and default history depth.
+
+/// Define a delta-state manager, its builder and their impls
+///
+/// Expands to `$name<S = InMemoryStore<$state>>` and `$builder` in the
+/// invoking module, with history capped at `$max_history` by default.
+macro_rules! delta_state_manager {
+    ($name:ident, $builder:ident, $state:ident, $max_history:literal) => {
+        use std::collections::{BTreeMap, VecDeque};
+        use std::fmt::Write;
+        use std::time::SystemTime;
+        #[cfg(feature = "mmap")]
+        use std::{fs::File, io, path::Path};
+        #[cfg(feature = "latency")]
+        use std::time::{Duration, Instant};
+
+        #[cfg(feature = "bincode")]
+        use $crate::error::BincodeError;
+        use $crate::check::{Op, SequenceMismatch};
+        use $crate::error::{BatchError, DeltaError, ParseError};
+        use $crate::event::AppliedEvent;
+        use $crate::fields::FromBits;
+        use $crate::fingerprint::fnv1a_64;
+        use $crate::history::{HistoryStore, InMemoryStore};
+        #[cfg(feature = "latency")]
+        use $crate::latency::LatencyHistogram;
+        use $crate::manager::DeltaManager;
+        use $crate::stats::DeltaStats;
+
+        #[doc = concat!(stringify!($name), " delta-state manager")]
+        ///
+        /// History is kept in an [`InMemoryStore`] unless another
+        /// [`HistoryStore`] backend is supplied through `with_store`.
+        #[derive(Debug, Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub struct $name<S = InMemoryStore<$state>> {
+            /// Initial state
+            initial_state: $state,
+            /// Initial states replaced by earlier loads, oldest first
+            initial_history: VecDeque<$state>,
+            /// Delta accumulator (XOR of all deltas)
+            accumulator: $state,
+            /// Delta history for rollback
+            history: S,
+            /// Optional user tag per history entry (kept in lockstep with history)
+            tags: VecDeque<Option<u64>>,
+            /// Commit markers as history positions (number of deltas before the marker)
+            commits: VecDeque<usize>,
+            /// Maximum history depth
+            max_history: usize,
+            /// Optional cap on the estimated history footprint in bytes
+            max_history_bytes: Option<usize>,
+            /// Peak history depth since the last load
+            max_history_seen: usize,
+            /// Whether mutation is currently blocked
+            frozen: bool,
+            /// Whether accumulate skips recording history
+            history_paused: bool,
+            /// Whether `load` has been called
+            loaded: bool,
+            /// Whether accumulating before `load` is rejected
+            strict_load: bool,
+            /// Rolling statistics over recent deltas, if enabled
+            delta_stats: Option<DeltaStats<$state>>,
+            /// Requested rollback counts, bucketed by next power of two
+            rollback_depths: BTreeMap<usize, u64>,
+            /// Arithmetic sum of accumulated delta values since the last load
+            magnitude_sum: u128,
+            /// Number of deltas accumulated over the manager's lifetime
+            accumulate_seq: u64,
+            /// History pressure threshold and the callback fired on crossing it
+            #[cfg_attr(feature = "serde", serde(skip))]
+            pressure_alarm: Option<(f32, fn(f32))>,
+            /// Whether history pressure was at or above the threshold at last check
+            pressure_high: bool,
+            /// Per-call accumulate durations
+            #[cfg(feature = "latency")]
+            #[cfg_attr(feature = "serde", serde(skip))]
+            accumulate_latency: LatencyHistogram,
+        }
+
+        #[doc = concat!("Builder for [`", stringify!($name), "`] managers")]
+        ///
+        #[doc = concat!("Obtained from [`", stringify!($name), "::builder`]; unset options match `new()`.")]
+        #[derive(Debug, Clone)]
+        pub struct $builder {
+            initial_state: Option<$state>,
+            track_history: bool,
+            growth_chunk: Option<usize>,
+            strict_load: bool,
+        }
+
+        impl $builder {
+            /// Set the state the manager is loaded with
+            pub fn initial_state(mut self, state: $state) -> Self {
+                self.initial_state = Some(state);
+                self
+            }
+
+            /// Choose whether the manager keeps delta history
+            ///
+            /// With `false` the manager behaves like `new_no_history()`: history
+            /// is never allocated, `rollback` is a no-op and `history_size()`
+            /// stays 0.
+            pub fn track_history(mut self, track: bool) -> Self {
+                self.track_history = track;
+                self
+            }
+
+            /// Grow history `chunk` entries at a time instead of doubling
+            ///
+            /// Smooths the allocation profile on memory-tight targets; growth
+            /// stops once history can hold `max_history` deltas. Unset keeps the
+            /// default amortized growth.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `chunk` is zero.
+            pub fn growth_chunk(mut self, chunk: usize) -> Self {
+                assert!(chunk > 0, concat!(stringify!($name), " growth chunk must be non-zero"));
+                self.growth_chunk = Some(chunk);
+                self
+            }
+
+            /// Reject accumulating before the first `load`
+            ///
+            /// Off by default, where an unloaded manager accumulates against a
+            /// baseline of 0. When on, the fallible accumulate methods return a
+            /// `NotLoaded` error and the infallible ones panic until `load` is
+            /// called. Setting `initial_state` counts as a
+            /// load.
+            pub fn strict_load(mut self, strict: bool) -> Self {
+                self.strict_load = strict;
+                self
+            }
+
+            /// Build the configured manager
+            pub fn build(self) -> $name {
+                let mut manager = if self.track_history {
+                    $name::new()
+                } else {
+                    $name::new_no_history()
+                };
+                if let (true, Some(chunk)) = (self.track_history, self.growth_chunk) {
+                    // One slot over max_history: a delta is pushed before the oldest is evicted
+                    manager.history = InMemoryStore::with_growth_chunk(chunk, manager.max_history + 1);
+                }
+                manager.strict_load = self.strict_load;
+                if let Some(state) = self.initial_state {
+                    manager.load(state);
+                }
+                manager
+            }
+        }
+
+        impl $name {
+            /// Estimated bytes retained per history entry (delta plus tag slot)
+            pub const HISTORY_ENTRY_BYTES: usize =
+                std::mem::size_of::<$state>() + std::mem::size_of::<Option<u64>>();
+
+            /// Number of prior initial states kept by `load`
+            pub const INITIAL_HISTORY_DEPTH: usize = 16;
+
+            /// Create a new delta-state manager
+            pub fn new() -> Self {
+                Self::with_store(InMemoryStore::new())
+            }
+
+            #[doc = concat!("Start configuring a manager with [`", stringify!($builder), "`]")]
+            pub fn builder() -> $builder {
+                $builder {
+                    initial_state: None,
+                    track_history: true,
+                    growth_chunk: None,
+                    strict_load: false,
+                }
+            }
+
+            /// Create a manager that keeps no delta history
+            ///
+            /// Skips all history bookkeeping on the hot path: `accumulate` and
+            /// `reconstruct` behave as usual, while `rollback` is a no-op that
+            /// returns 0.
+            pub fn new_no_history() -> Self {
+                Self {
+                    max_history: 0,
+                    ..Self::new()
+                }
+            }
+
+            /// Create a manager seeded at an already reconstructed state
+            ///
+            /// Equivalent to `new()` followed by `load(state)`.
+            pub fn from_state(state: $state) -> Self {
+                let mut manager = Self::new();
+                manager.load(state);
+                manager
+            }
+
+            /// Create a manager at `current` that can roll back to `origin`
+            ///
+            /// Loads `origin` and accumulates `origin ^ current` as a single
+            /// synthetic history entry, so `reconstruct()` returns `current` and
+            /// one rollback lands back on `origin`.
+            pub fn from_origin_and_current(origin: $state, current: $state) -> Self {
+                let mut manager = Self::from_state(origin);
+                manager.accumulate(origin ^ current);
+                manager
+            }
+
+            /// Replay a delta log in chunks, reporting progress after each one
+            ///
+            /// Folds `deltas` into a fresh manager loaded with `initial` and calls
+            /// `progress` with the cumulative number of deltas applied after every
+            /// chunk of `chunk` deltas (the last chunk may be shorter). The result
+            /// is the same as accumulating every delta in one go.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `chunk` is 0.
+            pub fn replay_with_progress(
+                initial: $state,
+                deltas: &[$state],
+                chunk: usize,
+                mut progress: impl FnMut(usize),
+            ) -> Self {
+                assert!(chunk > 0, concat!(stringify!($name), " replay chunk size must be non-zero"));
+                let mut manager = Self::from_state(initial);
+                let mut applied = 0;
+                for batch in deltas.chunks(chunk) {
+                    for &delta in batch {
+                        manager.accumulate(delta);
+                    }
+                    applied += batch.len();
+                    progress(applied);
+                }
+                manager
+            }
+
+            /// Create a manager seeded from a hex state string
+            ///
+            /// Accepts the output of `to_hex_state`, with or without a `0x` or
+            /// `0X` prefix. Sign characters are rejected.
+            pub fn from_hex_state(s: &str) -> Result<Self, ParseError> {
+                let digits = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+                // from_str_radix takes a leading `+`; parsing the sign on its own
+                // turns it into the same InvalidDigit error as any other non-hex
+                let digits = if digits.starts_with(['+', '-']) { &digits[..1] } else { digits };
+                let state = $state::from_str_radix(digits, 16).map_err(|source| ParseError { source })?;
+                Ok(Self::from_state(state))
+            }
+
+            /// Run `ops` against a fresh manager and a `Vec`-backed reference model
+            ///
+            /// After every op the manager's `reconstruct()` is compared with the
+            /// model, which evicts at the same `max_history`. Rollback counts
+            /// reach the manager unchanged, so its own clamping is exercised; with
+            /// the `strict` feature they are clamped to the retained depth first,
+            /// since an over-long rollback would panic. Meant for use in fuzz and
+            /// property tests; returns the first divergence found.
+            pub fn check_sequence(ops: &[Op<$state>]) -> Result<(), SequenceMismatch<$state>> {
+                let mut manager = Self::new();
+                let capacity = manager.max_history();
+                let mut initial = 0;
+                let mut evicted = 0;
+                let mut model: Vec<$state> = Vec::new();
+                for (index, op) in ops.iter().enumerate() {
+                    match *op {
+                        Op::Load(state) => {
+                            manager.load(state);
+                            initial = state;
+                            evicted = 0;
+                            model.clear();
+                        }
+                        Op::Accumulate(delta) => {
+                            manager.accumulate(delta);
+                            model.push(delta);
+                            if model.len() > capacity {
+                                evicted ^= model.remove(0);
+                            }
+                        }
+                        Op::Rollback(count) => {
+                            let clamped = count.min(model.len());
+                            manager.rollback(if cfg!(feature = "strict") { clamped } else { count });
+                            model.truncate(model.len() - clamped);
+                        }
+                    }
+                    let expected = model.iter().fold(initial ^ evicted, |state, delta| state ^ delta);
+                    let actual = manager.reconstruct();
+                    if actual != expected {
+                        return Err(SequenceMismatch {
+                            index,
+                            expected,
+                            actual,
+                        });
+                    }
+                }
+                Ok(())
+            }
+
+            /// Check that two delta orderings reach identical state from `initial`
+            ///
+            /// Folds each slice onto `initial` and compares the results. Because
+            /// XOR is commutative and associative this holds for any permutation
+            /// of the same deltas; it is a test helper for that property and does
+            /// not carry over to order-sensitive combinators such as hash chains.
+            pub fn states_equal_unordered(a: &[$state], b: &[$state], initial: $state) -> bool {
+                let fold = |deltas: &[$state]| deltas.iter().fold(initial, |state, delta| state ^ delta);
+                fold(a) == fold(b)
+            }
+
+            /// Get the number of deltas history can hold without reallocating
+            pub fn history_capacity(&self) -> usize {
+                self.history.capacity()
+            }
+
+            /// Load initial state and release the history's spare capacity
+            ///
+            /// Like `load`, but also shrinks the history buffers so a past burst
+            /// of accumulation does not pin its peak allocation across load cycles.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen.
+            pub fn load_shrinking(&mut self, initial_state: $state) {
+                self.load(initial_state);
+                self.history.shrink_to_fit();
+                self.tags.shrink_to_fit();
+                self.commits.shrink_to_fit();
+            }
+        }
+
+        impl<S: HistoryStore<$state>> $name<S> {
+            /// Create a manager keeping its history in `store`
+            ///
+            /// Any deltas already in `store` are discarded.
+            pub fn with_store(mut store: S) -> Self {
+                store.clear();
+                Self {
+                    initial_state: 0,
+                    initial_history: VecDeque::new(),
+                    accumulator: 0,
+                    history: store,
+                    tags: VecDeque::new(),
+                    commits: VecDeque::new(),
+                    max_history: $max_history,
+                    max_history_bytes: None,
+                    max_history_seen: 0,
+                    frozen: false,
+                    history_paused: false,
+                    loaded: false,
+                    strict_load: false,
+                    delta_stats: None,
+                    rollback_depths: BTreeMap::new(),
+                    magnitude_sum: 0,
+                    accumulate_seq: 0,
+                    pressure_alarm: None,
+                    pressure_high: false,
+                    #[cfg(feature = "latency")]
+                    accumulate_latency: LatencyHistogram::default(),
+                }
+            }
+
+            /// Load initial state (LOAD operation)
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen.
+            pub fn load(&mut self, initial_state: $state) {
+                self.assert_not_frozen("load");
+                let previous = std::mem::replace(&mut self.initial_state, initial_state);
+                if std::mem::replace(&mut self.loaded, true) {
+                    self.initial_history.push_back(previous);
+                    if self.initial_history.len() > <$name>::INITIAL_HISTORY_DEPTH {
+                        self.initial_history.pop_front();
+                    }
+                }
+                self.accumulator = 0;
+                self.history.clear();
+                self.tags.clear();
+                self.commits.clear();
+                self.max_history_seen = 0;
+                self.magnitude_sum = 0;
+                self.pressure_high = false;
+                if let Some(stats) = &mut self.delta_stats {
+                    stats.clear();
+                }
+            }
+
+            /// Load both the initial state and the accumulator from a snapshot
+            ///
+            /// `reconstruct()` immediately returns `initial_state ^ accumulator`.
+            /// History is cleared, so rollback has nothing to undo until new
+            /// deltas are accumulated.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen.
+            pub fn load_full(&mut self, initial_state: $state, accumulator: $state) {
+                self.load(initial_state);
+                self.accumulator = accumulator;
+            }
+
+            /// Replace the accumulator with `new` and return the previous one
+            ///
+            /// For double-buffered pipelines: the reader takes a consistent
+            /// snapshot while the writer starts over against the same initial
+            /// state. History is cleared, since its deltas describe the old
+            /// accumulator.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen.
+            pub fn swap_accumulator(&mut self, new: $state) -> $state {
+                self.assert_not_frozen("swap_accumulator");
+                self.history.clear();
+                self.tags.clear();
+                self.commits.clear();
+                std::mem::replace(&mut self.accumulator, new)
+            }
+
+            /// Accumulate delta (ACCUMULATE operation)
+            ///
+            /// XORs the delta into the accumulator.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen, or unloaded with `strict_load`.
+            pub fn accumulate(&mut self, delta: $state) {
+                self.push_delta(delta, None);
+            }
+
+            /// Accumulate delta, rejecting it instead of panicking
+            ///
+            /// Returns `Err(DeltaError::Frozen)` if the manager is frozen, or
+            /// `Err(DeltaError::NotLoaded)` if it was built with `strict_load`
+            /// and `load` has not been called yet, without applying the delta;
+            /// otherwise behaves like `accumulate`.
+            pub fn try_accumulate(&mut self, delta: $state) -> Result<(), DeltaError> {
+                self.check_writable()?;
+                self.accumulate(delta);
+                Ok(())
+            }
+
+            /// Accumulate delta unless it is zero
+            ///
+            /// Zero deltas are no-ops on state; skipping them keeps history and
+            /// counters (peak depth, stats, magnitude sum) meaningful. Returns
+            /// whether the delta was applied.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `delta` is non-zero and the manager is frozen, or
+            /// unloaded with `strict_load`.
+            pub fn accumulate_nonzero(&mut self, delta: $state) -> bool {
+                if delta == 0 {
+                    return false;
+                }
+                self.accumulate(delta);
+                true
+            }
+
+            /// Accumulate delta only if it stays within `writable_mask`
+            ///
+            /// A delta with any bit set outside the mask is rejected without
+            /// being applied, enforcing a field contract on reserved bits. Also
+            /// returns an error if the manager is frozen or unloaded with
+            /// `strict_load`.
+            pub fn accumulate_checked(
+                &mut self,
+                delta: $state,
+                writable_mask: $state,
+            ) -> Result<(), DeltaError> {
+                self.check_writable()?;
+                let reserved_bits = delta & !writable_mask;
+                if reserved_bits != 0 {
+                    return Err(DeltaError::MaskViolation {
+                        reserved_bits: u128::from(reserved_bits),
+                    });
+                }
+                self.accumulate(delta);
+                Ok(())
+            }
+
+            /// Accumulate delta and return an audit record of the change
+            ///
+            /// `seq` counts every delta accumulated by this manager, through any
+            /// accumulate method, and is not reset by `load`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen, or unloaded with `strict_load`.
+            pub fn accumulate_event(&mut self, delta: $state) -> AppliedEvent<$state> {
+                let seq = self.accumulate_seq;
+                self.accumulate(delta);
+                AppliedEvent {
+                    seq,
+                    delta,
+                    resulting_state: self.reconstruct(),
+                    timestamp: SystemTime::now(),
+                }
+            }
+
+            /// Accumulate delta annotated with a user tag
+            ///
+            /// The tag travels with the delta through history eviction and
+            /// rollback, so it can later be targeted by `rollback_to_tag`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen, or unloaded with `strict_load`.
+            pub fn accumulate_tagged(&mut self, delta: $state, tag: u64) {
+                self.push_delta(delta, Some(tag));
+            }
+
+            /// Adopt a divergent peer state, returning the conflict delta
+            ///
+            /// The conflict delta `reconstruct() ^ other_state` is accumulated as
+            /// an ordinary delta, so afterwards `reconstruct() == other_state` and
+            /// a single rollback restores the pre-merge state.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen, or unloaded with `strict_load`.
+            pub fn reconcile(&mut self, other_state: $state) -> $state {
+                let conflict = self.reconstruct() ^ other_state;
+                self.accumulate(conflict);
+                conflict
+            }
+
+            /// Set the masked bits, leaving the rest of the state untouched
+            ///
+            /// Accumulates exactly the XOR delta that turns on the bits of `mask`
+            /// that are currently off, so repeated calls are idempotent.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen, or unloaded with `strict_load`.
+            pub fn set_flags(&mut self, mask: $state) {
+                let delta = !self.reconstruct() & mask;
+                self.accumulate(delta);
+            }
+
+            /// Clear the masked bits, leaving the rest of the state untouched
+            ///
+            /// Accumulates exactly the XOR delta that turns off the bits of `mask`
+            /// that are currently on, so repeated calls are idempotent.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen, or unloaded with `strict_load`.
+            pub fn clear_flags(&mut self, mask: $state) {
+                let delta = self.reconstruct() & mask;
+                self.accumulate(delta);
+            }
+
+            /// Accumulate a stream of deltas, emitting the state after each one
+            ///
+            /// For every delta, applies it and then calls `sink` with the new
+            /// reconstructed state.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen, or unloaded with `strict_load`.
+            pub fn accumulate_streaming(
+                &mut self,
+                deltas: impl Iterator<Item = $state>,
+                mut sink: impl FnMut($state),
+            ) {
+                for delta in deltas {
+                    self.accumulate(delta);
+                    sink(self.reconstruct());
+                }
+            }
+
+            /// Validate a batch of deltas, then apply all of them or none
+            ///
+            /// Rejects the batch without touching any state if the manager is
+            /// frozen or unloaded with `strict_load`, or if the batch is larger
+            /// than the history can retain (count or byte cap), since it could
+            /// then no longer be rolled back as a unit. Managers without history,
+            /// and managers whose history is paused, record nothing for the
+            /// batch and accept it at any length; it cannot be rolled back.
+            pub fn try_accumulate_batch(&mut self, deltas: &[$state]) -> Result<(), BatchError> {
+                self.check_writable().map_err(|err| match err {
+                    DeltaError::NotLoaded => BatchError::NotLoaded,
+                    _ => BatchError::Frozen,
+                })?;
+                if self.max_history > 0 && !self.history_paused {
+                    let capacity = self.effective_max_history();
+                    if deltas.len() > capacity {
+                        return Err(BatchError::HistoryOverflow { index: capacity, capacity });
+                    }
+                }
+                for &delta in deltas {
+                    self.accumulate(delta);
+                }
+                Ok(())
+            }
+
+            /// Accumulate a mask flipping every bit in `[lo, hi)`
+            ///
+            /// Returns an error (without applying anything) if the range is empty
+            /// or extends past the state width, or if the manager is frozen or
+            /// unloaded with `strict_load`.
+            pub fn accumulate_bit_range(&mut self, lo: u32, hi: u32) -> Result<(), DeltaError> {
+                self.check_writable()?;
+                if lo >= hi || hi > $state::BITS {
+                    return Err(DeltaError::InvalidBitRange { lo, hi, width: $state::BITS });
+                }
+                let width = hi - lo;
+                let mask = if width == $state::BITS {
+                    $state::MAX
+                } else {
+                    ((1 << width) - 1) << lo
+                };
+                self.accumulate(mask);
+                Ok(())
+            }
+
+            /// Add `signed_delta` to the field at bits `[field_lo, field_lo + field_width)`
+            ///
+            /// Reads the field, adds with wrap-around at the field width, and
+            /// accumulates the XOR delta turning the old field bits into the new
+            /// ones, so rollback undoes the addition like any other delta. Bits
+            /// outside the field are untouched. Returns an error (without applying
+            /// anything) if the field is empty or extends past the state width,
+            /// or if the manager is frozen or unloaded with `strict_load`.
+            pub fn accumulate_signed(
+                &mut self,
+                field_lo: u32,
+                field_width: u32,
+                signed_delta: i64,
+            ) -> Result<(), DeltaError> {
+                self.check_writable()?;
+                let field_hi = field_lo.saturating_add(field_width);
+                if field_width == 0 || field_hi > $state::BITS {
+                    return Err(DeltaError::InvalidBitRange {
+                        lo: field_lo,
+                        hi: field_hi,
+                        width: $state::BITS,
+                    });
+                }
+                let mask = $state::MAX >> ($state::BITS - field_width);
+                let current = (self.reconstruct() >> field_lo) & mask;
+                let updated = current.wrapping_add(signed_delta as $state) & mask;
+                self.accumulate((current ^ updated) << field_lo);
+                Ok(())
+            }
+
+            fn check_writable(&self) -> Result<(), DeltaError> {
+                if self.frozen {
+                    return Err(DeltaError::Frozen);
+                }
+                if self.strict_load && !self.loaded {
+                    return Err(DeltaError::NotLoaded);
+                }
+                Ok(())
+            }
+
+            fn push_delta(&mut self, delta: $state, tag: Option<u64>) {
+                self.assert_not_frozen("accumulate");
+                if self.strict_load && !self.loaded {
+                    panic!(concat!(stringify!($name), " is not loaded: accumulate before load() with strict_load"));
+                }
+                #[cfg(feature = "latency")]
+                let started = Instant::now();
+                if self.max_history > 0 && !self.history_paused {
+                    // Save to history
+                    self.history.push(delta);
+                    self.tags.push_back(tag);
+                    if self.history.len() > self.max_history {
+                        self.evict_oldest();
+                    }
+                    self.enforce_history_bytes();
+                    self.max_history_seen = self.max_history_seen.max(self.history.len());
+                    self.check_pressure();
+                }
+                if let Some(stats) = &mut self.delta_stats {
+                    stats.push(delta);
+                }
+                self.magnitude_sum = self.magnitude_sum.saturating_add(u128::from(delta));
+                // XOR delta into accumulator
+                self.accumulator ^= delta;
+                self.accumulate_seq += 1;
+                #[cfg(feature = "latency")]
+                self.accumulate_latency.record(started.elapsed());
+            }
+
+            fn evict_oldest(&mut self) {
+                self.history.pop_front();
+                self.tags.pop_front();
+                // Markers before the evicted delta fall out of the window
+                self.commits.retain_mut(|position| {
+                    *position = position.wrapping_sub(1);
+                    *position != usize::MAX
+                });
+            }
+
+            fn enforce_history_bytes(&mut self) {
+                if let Some(limit) = self.max_history_bytes {
+                    while self.history_bytes() > limit {
+                        self.evict_oldest();
+                    }
+                }
+            }
+
+            /// Reconstruct current state (READ operation)
+            ///
+            /// Returns current_state = initial_state XOR accumulator
+            pub fn reconstruct(&self) -> $state {
+                self.initial_state ^ self.accumulator
+            }
+
+            /// Preview the state `delta` would produce, without applying it
+            pub fn preview(&self, delta: $state) -> $state {
+                self.reconstruct() ^ delta
+            }
+
+            /// Reconstruct the current state as big-endian bytes
+            pub fn reconstruct_be_bytes(&self) -> [u8; std::mem::size_of::<$state>()] {
+                self.reconstruct().to_be_bytes()
+            }
+
+            /// Reconstruct the current state as little-endian bytes
+            pub fn reconstruct_le_bytes(&self) -> [u8; std::mem::size_of::<$state>()] {
+                self.reconstruct().to_le_bytes()
+            }
+
+            /// Reconstruct the current state unpacked into a typed value
+            pub fn reconstruct_as<T: FromBits<$state>>(&self) -> T {
+                T::from_bits(self.reconstruct())
+            }
+
+            /// Reconstruct the state as if `delta` had never been applied
+            ///
+            /// Returns `reconstruct() ^ delta` without mutating anything. XOR is
+            /// commutative, so this holds regardless of where the delta was
+            /// applied, but it is only meaningful if `delta` actually was applied.
+            pub fn reconstruct_without(&self, delta: $state) -> $state {
+                self.reconstruct() ^ delta
+            }
+
+            /// Get a 64-bit fingerprint of the reconstructed state
+            ///
+            /// FNV-1a over the little-endian state bytes. Equal states always
+            /// share a fingerprint regardless of how they were reached.
+            pub fn fingerprint(&self) -> u64 {
+                fnv1a_64(&self.reconstruct().to_le_bytes())
+            }
+
+            /// Get the fingerprint as a fixed byte array for the wire
+            ///
+            /// Little-endian and heap-free, sized to drop straight into a gossip
+            /// packet.
+            pub fn fingerprint_bytes(&self) -> [u8; 8] {
+                self.fingerprint().to_le_bytes()
+            }
+
+            /// Check a peer's fingerprint against this state without allocating
+            pub fn matches_fingerprint(&self, fp: &[u8; 8]) -> bool {
+                self.fingerprint_bytes() == *fp
+            }
+
+            /// Count the bits where the current state differs from `reference`
+            pub fn hamming_distance(&self, reference: $state) -> u32 {
+                (self.reconstruct() ^ reference).count_ones()
+            }
+
+            /// Check if accumulator is zero (STATUS operation)
+            pub fn is_accumulator_zero(&self) -> bool {
+                self.accumulator == 0
+            }
+
+            /// Rollback the last N delta operations
+            ///
+            /// Returns the number of deltas actually rolled back. The removed
+            /// deltas are XOR-folded in one pass and the history truncated once,
+            /// rather than popped one at a time as `rollback_collect` does.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen. With the `strict` feature, also
+            /// panics if `count` exceeds the retained history instead of clamping.
+            pub fn rollback(&mut self, count: usize) -> usize {
+                let actual_count = self.begin_rollback(count);
+                let keep = self.history.len() - actual_count;
+                // XOR removes the deltas (self-inverse property)
+                self.accumulator ^= self.history.iter().skip(keep).fold(0, |folded, &delta| folded ^ delta);
+                self.history.truncate(keep);
+                self.tags.truncate(keep);
+                self.drop_stale_commits();
+                self.check_pressure();
+                actual_count
+            }
+
+            /// Estimate the XOR operations a `rollback(count)` would perform
+            ///
+            /// One XOR per delta actually removed, so the requested count clamped
+            /// to the retained history.
+            pub fn estimate_rollback_ops(&self, count: usize) -> usize {
+                count.min(self.history.len())
+            }
+
+            /// Rollback the last N delta operations, returning the removed deltas
+            ///
+            /// Deltas are returned newest-first, in the order they were XORed out.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen. With the `strict` feature, also
+            /// panics if `count` exceeds the retained history instead of clamping.
+            pub fn rollback_collect(&mut self, count: usize) -> Vec<$state> {
+                let actual_count = self.begin_rollback(count);
+                let mut removed = Vec::with_capacity(actual_count);
+                for _ in 0..actual_count {
+                    if let Some(delta) = self.history.pop_back() {
+                        self.tags.pop_back();
+                        // XOR removes the delta (self-inverse property)
+                        self.accumulator ^= delta;
+                        removed.push(delta);
+                    }
+                }
+                self.drop_stale_commits();
+                self.check_pressure();
+                removed
+            }
+
+            /// Shared rollback prologue: checks, histogram bucket, clamped count
+            fn begin_rollback(&mut self, count: usize) -> usize {
+                self.assert_not_frozen("rollback");
+                // Without history there is nothing to exceed: rolling back is a no-op
+                if cfg!(feature = "strict") && self.max_history > 0 {
+                    assert!(
+                        count <= self.history.len(),
+                        concat!(stringify!($name), " rollback of {} exceeds retained history of {}"),
+                        count,
+                        self.history.len()
+                    );
+                }
+                let bucket = if count == 0 { 0 } else { count.next_power_of_two() };
+                *self.rollback_depths.entry(bucket).or_insert(0) += 1;
+                count.min(self.history.len())
+            }
+
+            /// Drop commit markers that now point past the end of history
+            fn drop_stale_commits(&mut self) {
+                while self.commits.back().is_some_and(|&position| position > self.history.len()) {
+                    self.commits.pop_back();
+                }
+            }
+
+            /// Rollback until the state equals `target`, up to `max_steps` deltas
+            ///
+            /// Walks back through history one delta at a time looking for
+            /// `target` and, once found, rolls back that many deltas. Returns the
+            /// number of steps (0 if already at `target`), or `None` with the
+            /// manager untouched if `target` is not reached within `max_steps`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen.
+            pub fn rollback_to_state(&mut self, target: $state, max_steps: usize) -> Option<usize> {
+                self.assert_not_frozen("rollback");
+                let mut state = self.reconstruct();
+                if state == target {
+                    return Some(0);
+                }
+                let steps = self
+                    .history
+                    .iter()
+                    .rev()
+                    .take(max_steps)
+                    .position(|delta| {
+                        state ^= delta;
+                        state == target
+                    })?
+                    + 1;
+                self.rollback(steps);
+                Some(steps)
+            }
+
+            /// Rollback until the most recent delta bearing `tag` has been removed
+            ///
+            /// Returns the number of deltas rolled back, or 0 if no retained
+            /// delta carries the tag.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen. With the `strict` feature, also
+            /// panics if no retained delta carries the tag.
+            pub fn rollback_to_tag(&mut self, tag: u64) -> usize {
+                self.assert_not_frozen("rollback");
+                match self.tags.iter().rposition(|t| *t == Some(tag)) {
+                    Some(index) => self.rollback(self.history.len() - index),
+                    None if cfg!(feature = "strict") => {
+                        panic!(concat!(stringify!($name), " rollback_to_tag: no retained delta tagged {}"), tag)
+                    }
+                    None => 0,
+                }
+            }
+
+            /// Mark a commit boundary after the most recently accumulated delta
+            ///
+            /// Markers are kept apart from the delta history, so they never look
+            /// like deltas. A marker is dropped once the delta before it is
+            /// rolled back, or once eviction pushes it out of the retained window.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen.
+            pub fn mark_commit(&mut self) {
+                self.assert_not_frozen("mark_commit");
+                let position = self.history.len();
+                if self.commits.back() != Some(&position) {
+                    self.commits.push_back(position);
+                }
+            }
+
+            /// Rollback to just after the most recent commit marker
+            ///
+            /// Returns the number of deltas rolled back, or 0 if no commit marker
+            /// is retained.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen. With the `strict` feature, also
+            /// panics if no commit marker is retained.
+            pub fn rollback_to_last_commit(&mut self) -> usize {
+                self.assert_not_frozen("rollback");
+                match self.commits.back() {
+                    Some(&position) => self.rollback(self.history.len() - position),
+                    None if cfg!(feature = "strict") => {
+                        panic!(concat!(stringify!($name), " rollback_to_last_commit: no commit marker retained"))
+                    }
+                    None => 0,
+                }
+            }
+
+            /// Remove the most recent occurrence of `delta` from history
+            ///
+            /// XOR is commutative, so XORing the delta back out keeps the
+            /// accumulator correct regardless of its position. Returns whether a
+            /// matching delta was found.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen.
+            pub fn remove_delta(&mut self, delta: $state) -> bool {
+                self.assert_not_frozen("remove_delta");
+                let found = self.history.iter().rposition(|d| *d == delta);
+                match found {
+                    Some(index) => {
+                        self.history.remove(index);
+                        self.tags.remove(index);
+                        for position in self.commits.iter_mut().filter(|position| **position > index) {
+                            *position -= 1;
+                        }
+                        self.accumulator ^= delta;
+                        true
+                    }
+                    None => false,
+                }
+            }
+
+            /// Remove pairs of identical deltas from history
+            ///
+            /// Two equal deltas XOR to zero, so dropping both leaves the
+            /// accumulator and `reconstruct` untouched while shrinking history.
+            /// Each delta is paired with the next unpaired occurrence of the same
+            /// value. This changes rollback granularity: rolling back N deltas
+            /// afterwards may undo operations that were further back before
+            /// compaction. Returns the number of deltas removed.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen.
+            pub fn compact_canceling(&mut self) -> usize {
+                self.assert_not_frozen("compact_canceling");
+                let deltas: Vec<$state> = self.history.iter().copied().collect();
+                let mut keep = vec![true; deltas.len()];
+                let mut unpaired = BTreeMap::new();
+                for (index, &delta) in deltas.iter().enumerate() {
+                    match unpaired.remove(&delta) {
+                        Some(earlier) => {
+                            keep[earlier] = false;
+                            keep[index] = false;
+                        }
+                        None => {
+                            unpaired.insert(delta, index);
+                        }
+                    }
+                }
+                let removed = keep.iter().filter(|kept| !**kept).count();
+                if removed == 0 {
+                    return 0;
+                }
+
+                // survivors[p] = deltas kept among the first p, for remapping commits
+                let mut survivors = Vec::with_capacity(deltas.len() + 1);
+                survivors.push(0);
+                let tags = std::mem::take(&mut self.tags);
+                self.history.clear();
+                for ((delta, tag), kept) in deltas.into_iter().zip(tags).zip(keep) {
+                    if kept {
+                        self.history.push(delta);
+                        self.tags.push_back(tag);
+                    }
+                    survivors.push(self.history.len());
+                }
+                for position in std::mem::take(&mut self.commits) {
+                    let position = survivors[position];
+                    if self.commits.back() != Some(&position) {
+                        self.commits.push_back(position);
+                    }
+                }
+                removed
+            }
+
+            /// Split retained history at `index`, returning the suffix as a new manager
+            ///
+            /// This manager keeps deltas `[0, index)`, with its accumulator
+            /// adjusted so it reconstructs the state at `index`. The returned
+            /// manager starts from that state and holds deltas `[index, len)`
+            /// with their tags and commit markers, so it reconstructs this
+            /// manager's original state. History limits, the store's growth
+            /// settings, `strict_load`, the pressure alarm and the delta-stats
+            /// window carry over to it (the stats start empty), and it counts as
+            /// loaded since it has a real baseline.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` exceeds the retained history or the manager is
+            /// frozen.
+            pub fn split_at(&mut self, index: usize) -> Self
+            where
+                S: Default,
+            {
+                self.assert_not_frozen("split_at");
+                assert!(
+                    index <= self.history.len(),
+                    concat!(stringify!($name), " split index {} exceeds retained history of {}"),
+                    index,
+                    self.history.len()
+                );
+                let mut suffix = Vec::with_capacity(self.history.len() - index);
+                while self.history.len() > index {
+                    if let (Some(delta), Some(tag)) = (self.history.pop_back(), self.tags.pop_back()) {
+                        suffix.push((delta, tag));
+                    }
+                }
+                let suffix_xor = suffix.iter().fold(0, |folded, &(delta, _)| folded ^ delta);
+                self.accumulator ^= suffix_xor;
+
+                let mut tail = Self::with_store(self.history.empty_like());
+                tail.max_history = self.max_history;
+                tail.max_history_bytes = self.max_history_bytes;
+                tail.strict_load = self.strict_load;
+                tail.loaded = true;
+                tail.initial_state = self.reconstruct();
+                tail.accumulator = suffix_xor;
+                for (delta, tag) in suffix.into_iter().rev() {
+                    tail.history.push(delta);
+                    tail.tags.push_back(tag);
+                }
+                tail.max_history_seen = tail.history.len();
+                tail.delta_stats = self.delta_stats.as_ref().map(|stats| DeltaStats::new(stats.window()));
+                if let Some((threshold, callback)) = self.pressure_alarm {
+                    tail.set_pressure_alarm(threshold, callback);
+                }
+                while self.commits.back().is_some_and(|&position| position > index) {
+                    if let Some(position) = self.commits.pop_back() {
+                        tail.commits.push_front(position - index);
+                    }
+                }
+                tail
+            }
+
+            /// Iterate over initial states replaced by `load`, oldest first
+            ///
+            /// Each load pushes the baseline it overwrites, except the first load
+            /// of a manager that had no baseline yet; only the last
+            /// `INITIAL_HISTORY_DEPTH` are kept.
+            pub fn previous_initials(&self) -> impl Iterator<Item = &$state> {
+                self.initial_history.iter()
+            }
+
+            /// Get the current accumulator value
+            pub fn get_accumulator(&self) -> $state {
+                self.accumulator
+            }
+
+            /// Get the initial state
+            pub fn get_initial_state(&self) -> $state {
+                self.initial_state
+            }
+
+            /// Get the number of deltas in history
+            pub fn history_size(&self) -> usize {
+                self.history.len()
+            }
+
+            /// Get the maximum history depth
+            pub fn max_history(&self) -> usize {
+                self.max_history
+            }
+
+            /// Get the estimated history footprint in bytes
+            pub fn history_bytes(&self) -> usize {
+                self.history.len() * <$name>::HISTORY_ENTRY_BYTES
+            }
+
+            /// Cap the estimated history footprint in bytes, in addition to the count cap
+            ///
+            /// While set, the oldest deltas are evicted until the footprint is at
+            /// or under `limit`. Applies immediately to the retained history.
+            /// `None` removes the byte cap.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the manager is frozen.
+            pub fn set_max_history_bytes(&mut self, limit: Option<usize>) {
+                self.assert_not_frozen("set_max_history_bytes");
+                self.max_history_bytes = limit;
+                self.enforce_history_bytes();
+            }
+
+            /// Get the byte cap on history, if any
+            pub fn max_history_bytes(&self) -> Option<usize> {
+                self.max_history_bytes
+            }
+
+            /// Get the peak history depth reached since the last load
+            ///
+            /// A peak at `max_history` means older deltas have been evicted.
+            pub fn peak_history_size(&self) -> usize {
+                self.max_history_seen
+            }
+
+            /// Start tracking rolling statistics over the last `window` deltas
+            ///
+            /// Statistics cover accumulated deltas, independent of history
+            /// retention and rollback, and are reset on `load`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `window` is zero.
+            pub fn enable_delta_stats(&mut self, window: usize) {
+                self.delta_stats = Some(DeltaStats::new(window));
+            }
+
+            /// Get the accumulate latency at percentile `p` (0 to 100)
+            ///
+            /// Every accumulate is timed into an HDR-style histogram with about 6%
+            /// precision; the reported value is the upper bound of the matching
+            /// bucket. Samples survive `load`. Returns zero before the first
+            /// accumulate.
+            #[cfg(feature = "latency")]
+            pub fn accumulate_latency_percentile(&self, p: f64) -> Duration {
+                self.accumulate_latency.percentile(p)
+            }
+
+            /// Get how full history is, as a fraction of its effective capacity
+            ///
+            /// The capacity is `max_history`, or fewer entries if
+            /// `max_history_bytes` evicts sooner. Returns 0.0 when history is
+            /// disabled, and 1.0 when the byte cap is too small to hold any entry.
+            pub fn history_pressure(&self) -> f32 {
+                if self.max_history == 0 {
+                    return 0.0;
+                }
+                match self.effective_max_history() {
+                    0 => 1.0,
+                    capacity => self.history.len() as f32 / capacity as f32,
+                }
+            }
+
+            fn effective_max_history(&self) -> usize {
+                match self.max_history_bytes {
+                    Some(limit) => self.max_history.min(limit / <$name>::HISTORY_ENTRY_BYTES),
+                    None => self.max_history,
+                }
+            }
+
+            /// Call `callback` whenever history pressure rises to `threshold`
+            ///
+            /// The callback receives the current pressure and fires once per
+            /// crossing: it is re-armed only after a rollback or `load` brings
+            /// pressure back below the threshold. Replaces any previous alarm.
+            pub fn set_pressure_alarm(&mut self, threshold: f32, callback: fn(f32)) {
+                self.pressure_alarm = Some((threshold, callback));
+                self.pressure_high = self.history_pressure() >= threshold;
+            }
+
+            /// Remove the history pressure alarm
+            pub fn clear_pressure_alarm(&mut self) {
+                self.pressure_alarm = None;
+                self.pressure_high = false;
+            }
+
+            fn check_pressure(&mut self) {
+                if let Some((threshold, callback)) = self.pressure_alarm {
+                    let pressure = self.history_pressure();
+                    let high = pressure >= threshold;
+                    if high && !self.pressure_high {
+                        callback(pressure);
+                    }
+                    self.pressure_high = high;
+                }
+            }
+
+            /// Stop tracking rolling delta statistics
+            pub fn disable_delta_stats(&mut self) {
+                self.delta_stats = None;
+            }
+
+            /// Get the rolling delta statistics, if enabled
+            pub fn delta_stats(&self) -> Option<&DeltaStats<$state>> {
+                self.delta_stats.as_ref()
+            }
+
+            /// Get the arithmetic sum of accumulated delta values since the last load
+            ///
+            /// An analytics counter independent of the XOR accumulator, useful for
+            /// spotting runaway updates. Rollback does not subtract from it, and it
+            /// saturates at `u128::MAX` instead of wrapping.
+            pub fn magnitude_sum(&self) -> u128 {
+                self.magnitude_sum
+            }
+
+            /// Get a histogram of requested rollback depths
+            ///
+            /// Keys are buckets: 0 for empty rollbacks, otherwise the next power
+            /// of two at or above the requested count (1, 2, 4, 8, ...). Values
+            /// are how many rollbacks fell into each bucket. Counts are recorded
+            /// as requested, before clamping to the retained history, and persist
+            /// across `load`.
+            pub fn rollback_depth_histogram(&self) -> BTreeMap<usize, u64> {
+                self.rollback_depths.clone()
+            }
+
+            /// Format the current state as a zero-padded `0x` hex string
+            pub fn to_hex_state(&self) -> String {
+                format!("0x{:0width$X}", self.reconstruct(), width = ($state::BITS / 4) as usize)
+            }
+
+            /// Dump the retained history as hex lines for diagnostics
+            ///
+            /// The first line is a header with the initial state and accumulator,
+            /// followed by one zero-padded hex line per retained delta, oldest
+            /// first.
+            pub fn dump_history(&self) -> String {
+                let width = ($state::BITS / 4) as usize;
+                let mut dump = String::new();
+                let _ = writeln!(
+                    dump,
+                    concat!(stringify!($name), " initial=0x{:0width$X} accumulator=0x{:0width$X} deltas={}"),
+                    self.initial_state,
+                    self.accumulator,
+                    self.history.len(),
+                    width = width,
+                );
+                for delta in self.history.iter() {
+                    let _ = writeln!(dump, "0x{:0width$X}", delta);
+                }
+                dump
+            }
+
+            /// Export the retained history as bytes
+            ///
+            /// Same layout as `export_history_to`, collected into one buffer.
+            pub fn export_history(&self) -> Vec<u8> {
+                let width = std::mem::size_of::<$state>();
+                let mut bytes = Vec::with_capacity(width * (self.history.len() + 2) + 8);
+                self.export_history_to(&mut bytes)
+                    .expect("writing history to a Vec cannot fail");
+                bytes
+            }
+
+            /// Stream the retained history to `w`
+            ///
+            /// Writes a header of the initial state, the accumulator and the
+            /// delta count (a 64-bit integer for every state width), followed by
+            /// each retained delta oldest first, all little-endian. Deltas are
+            /// written in bounded chunks, so exporting a large history never
+            /// buffers it whole.
+            pub fn export_history_to(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+                const CHUNK_DELTAS: usize = 256;
+                const WIDTH: usize = std::mem::size_of::<$state>();
+
+                w.write_all(&self.initial_state.to_le_bytes())?;
+                w.write_all(&self.accumulator.to_le_bytes())?;
+                w.write_all(&(self.history.len() as u64).to_le_bytes())?;
+
+                let mut chunk = [0u8; CHUNK_DELTAS * WIDTH];
+                let mut filled = 0;
+                for delta in self.history.iter() {
+                    chunk[filled..filled + WIDTH].copy_from_slice(&delta.to_le_bytes());
+                    filled += WIDTH;
+                    if filled == chunk.len() {
+                        w.write_all(&chunk)?;
+                        filled = 0;
+                    }
+                }
+                w.write_all(&chunk[..filled])
+            }
+
+            /// Iterate over retained deltas, oldest first
+            pub fn iter_history(&self) -> S::Iter<'_> {
+                self.history.iter()
+            }
+
+            /// Iterate over retained deltas with the number of bits each flipped
+            ///
+            /// Oldest first. A delta with an unexpectedly high popcount stands out
+            /// as one that touched more of the state than intended.
+            pub fn iter_delta_effects(&self) -> impl Iterator<Item = ($state, u32)> + '_ {
+                self.history.iter().map(|&delta| (delta, delta.count_ones()))
+            }
+
+            /// Find the first index where two managers' retained histories differ
+            ///
+            /// Walks both histories in lockstep and returns the first index whose
+            /// deltas differ, or the length of the shorter history if one is a
+            /// prefix of the other. Returns `None` if the histories are identical.
+            pub fn first_divergence<T: HistoryStore<$state>>(&self, other: &$name<T>) -> Option<usize> {
+                let mut ours = self.iter_history();
+                let mut theirs = other.iter_history();
+                let mut index = 0;
+                loop {
+                    match (ours.next(), theirs.next()) {
+                        (None, None) => return None,
+                        (Some(a), Some(b)) if a == b => index += 1,
+                        _ => return Some(index),
+                    }
+                }
+            }
+
+            /// Iterate over `(index, state)` pairs for the retained history
+            ///
+            /// `state` is the reconstructed state right after the delta at
+            /// `index` was applied, computed with a single running XOR fold.
+            /// The last yielded state equals `reconstruct()`.
+            pub fn iter_states(&self) -> impl Iterator<Item = (usize, $state)> + '_ {
+                let base = self.reconstruct() ^ self.history.iter().fold(0, |acc, d| acc ^ d);
+                self.history.iter().enumerate().scan(base, |state, (index, delta)| {
+                    *state ^= delta;
+                    Some((index, *state))
+                })
+            }
+
+            /// Freeze the manager, blocking `load`, `accumulate` and `rollback`
+            ///
+            /// Every method that changes state, history or commit markers panics
+            /// while frozen, except the `try_*`-style methods returning `Result`,
+            /// which return a `Frozen` error instead. Reads keep working, as do
+            /// settings that only affect future deltas: `pause_history`,
+            /// `resume_history`, the delta-stats and pressure-alarm toggles.
+            pub fn freeze(&mut self) {
+                self.frozen = true;
+            }
+
+            /// Re-enable mutation after `freeze`
+            pub fn unfreeze(&mut self) {
+                self.frozen = false;
+            }
+
+            /// Check if the manager is frozen
+            pub fn is_frozen(&self) -> bool {
+                self.frozen
+            }
+
+            /// Stop recording history while still accepting deltas
+            ///
+            /// While paused, accumulate updates the accumulator but pushes
+            /// nothing to history, so those deltas can never be rolled back;
+            /// rolling back past them leaves their effect in place. Already
+            /// retained history is kept.
+            pub fn pause_history(&mut self) {
+                self.history_paused = true;
+            }
+
+            /// Resume recording history after `pause_history`
+            pub fn resume_history(&mut self) {
+                self.history_paused = false;
+            }
+
+            /// Check if `load` has been called on this manager
+            pub fn is_loaded(&self) -> bool {
+                self.loaded
+            }
+
+            /// Check if history recording is paused
+            pub fn is_history_paused(&self) -> bool {
+                self.history_paused
+            }
+
+            fn assert_not_frozen(&self, operation: &str) {
+                if self.frozen {
+                    panic!(concat!(stringify!($name), " is frozen: {} is not allowed until unfreeze()"), operation);
+                }
+            }
+
+        }
+
+        #[cfg(feature = "bincode")]
+        impl $name {
+            /// Current bincode format version, written as the leading byte
+            ///
+            /// Bump this whenever a serialized field is added, removed,
+            /// reordered or changes type, so older payloads are rejected
+            /// instead of misread.
+            pub const BINCODE_VERSION: u8 = 1;
+
+            /// Serialize to bincode, prefixed with a format version byte
+            pub fn to_bincode(&self) -> Result<Vec<u8>, BincodeError> {
+                let mut bytes = vec![Self::BINCODE_VERSION];
+                bincode::serde::encode_into_std_write(self, &mut bytes, bincode::config::standard())
+                    .map_err(BincodeError::Encode)?;
+                Ok(bytes)
+            }
+
+            /// Deserialize from versioned bincode produced by `to_bincode`
+            ///
+            /// Rejects input whose version byte is not `BINCODE_VERSION`, input
+            /// with trailing bytes, and payloads whose history, tags and commit
+            /// markers are inconsistent with each other or with `max_history`.
+            pub fn from_bincode(bytes: &[u8]) -> Result<Self, BincodeError> {
+                let (&version, payload) = bytes.split_first().ok_or(BincodeError::MissingVersion)?;
+                if version != Self::BINCODE_VERSION {
+                    return Err(BincodeError::UnsupportedVersion(version));
+                }
+                let (manager, consumed): (Self, usize) =
+                    bincode::serde::decode_from_slice(payload, bincode::config::standard())
+                        .map_err(BincodeError::Decode)?;
+                if consumed != payload.len() {
+                    return Err(BincodeError::Invalid("trailing bytes after payload"));
+                }
+                let len = manager.history.len();
+                if manager.tags.len() != len {
+                    return Err(BincodeError::Invalid("tag count does not match history"));
+                }
+                if len > manager.max_history {
+                    return Err(BincodeError::Invalid("history exceeds max_history"));
+                }
+                let mut previous = 0;
+                for &position in &manager.commits {
+                    if position < previous || position > len {
+                        return Err(BincodeError::Invalid("commit marker out of order or past history"));
+                    }
+                    previous = position;
+                }
+                Ok(manager)
+            }
+        }
+
+        #[cfg(feature = "mmap")]
+        impl $name {
+            /// Replay an append-only delta log from disk
+            ///
+            /// The file is memory-mapped and read as consecutive little-endian
+            /// deltas, folded into a fresh manager loaded with `initial`. A file
+            /// whose length is not a whole number of deltas is rejected with
+            /// `InvalidData` rather than dropping the trailing bytes.
+            pub fn replay_from_mmap(initial: $state, path: &Path) -> io::Result<Self> {
+                const WIDTH: usize = std::mem::size_of::<$state>();
+
+                let file = File::open(path)?;
+                // SAFETY: the map is read-only and dropped before returning; the log
+                // must not be truncated by another process while it is replayed.
+                let log = unsafe { memmap2::Mmap::map(&file)? };
+                if log.len() % WIDTH != 0 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "delta log has {} trailing bytes after {} whole deltas",
+                            log.len() % WIDTH,
+                            log.len() / WIDTH
+                        ),
+                    ));
+                }
+
+                let mut manager = Self::from_state(initial);
+                for chunk in log.chunks_exact(WIDTH) {
+                    let mut bytes = [0u8; WIDTH];
+                    bytes.copy_from_slice(chunk);
+                    manager.accumulate($state::from_le_bytes(bytes));
+                }
+                Ok(manager)
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<S: HistoryStore<$state>> DeltaManager for $name<S> {
+            type State = $state;
+
+            fn load(&mut self, initial_state: $state) {
+                $name::load(self, initial_state)
+            }
+
+            fn accumulate(&mut self, delta: $state) {
+                $name::accumulate(self, delta)
+            }
+
+            fn try_accumulate(&mut self, delta: $state) -> Result<(), DeltaError> {
+                $name::try_accumulate(self, delta)
+            }
+
+            fn reconstruct(&self) -> $state {
+                $name::reconstruct(self)
+            }
+
+            fn rollback(&mut self, count: usize) -> usize {
+                $name::rollback(self, count)
+            }
+
+            fn history_size(&self) -> usize {
+                $name::history_size(self)
+            }
+        }
+    };
+}
+
+pub(crate) use delta_state_manager;
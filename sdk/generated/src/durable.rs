@@ -0,0 +1,144 @@
+//! Write-ahead logging over a delta-state manager
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use crate::manager::DeltaManager;
+
+/// Bytes per log record: sequence number then delta, both little-endian
+const RECORD_BYTES: usize = 16;
+
+/// Storage a [`DurableDeltaState`] appends its records to
+///
+/// Implemented for `File`; other implementations let the log live
+/// somewhere else, or fail on demand in tests.
+pub trait DeltaLog: Read + Write {
+    /// Flush written data to durable storage
+    fn sync_data(&mut self) -> io::Result<()>;
+
+    /// Truncate the log to `len` bytes and make that durable
+    fn truncate(&mut self, len: u64) -> io::Result<()>;
+}
+
+impl DeltaLog for File {
+    fn sync_data(&mut self) -> io::Result<()> {
+        File::sync_data(self)
+    }
+
+    fn truncate(&mut self, len: u64) -> io::Result<()> {
+        self.set_len(len)?;
+        self.sync_all()
+    }
+}
+
+/// Wrapper that appends each delta to an fsync'd log before applying it
+///
+/// On `open`, every complete record already in the log is replayed into
+/// the manager, so a delta that reached the log but not memory before a
+/// crash is still applied. A torn trailing record from a crash mid-append
+/// is truncated away.
+#[derive(Debug)]
+pub struct DurableDeltaState<M, L = File> {
+    inner: M,
+    log: L,
+    next_seq: u64,
+    poisoned: bool,
+}
+
+impl<M: DeltaManager<State = u64>> DurableDeltaState<M> {
+    /// Open or create the log at `path` and replay it into `inner`
+    ///
+    /// `inner` should already be loaded with the state the log was
+    /// started from. Returns `InvalidData` if the log's sequence numbers
+    /// are not consecutive from 0.
+    pub fn open(path: &Path, inner: M) -> io::Result<Self> {
+        let log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+        Self::with_log(log, inner)
+    }
+}
+
+impl<M: DeltaManager<State = u64>, L: DeltaLog> DurableDeltaState<M, L> {
+    /// Replay `log` from its current position into `inner`
+    ///
+    /// Same as [`open`](DurableDeltaState::open) for an already-opened
+    /// log; writes must append to its end.
+    pub fn with_log(mut log: L, mut inner: M) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        log.read_to_end(&mut bytes)?;
+
+        let mut next_seq = 0;
+        for record in bytes.chunks_exact(RECORD_BYTES) {
+            let (seq, delta) = record.split_at(8);
+            let seq = u64::from_le_bytes(seq.try_into().expect("record holds a u64 sequence"));
+            if seq != next_seq {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("delta log record {} has sequence {}", next_seq, seq),
+                ));
+            }
+            inner.accumulate(u64::from_le_bytes(delta.try_into().expect("record holds a u64 delta")));
+            next_seq += 1;
+        }
+        let complete = bytes.len() - bytes.len() % RECORD_BYTES;
+        if complete != bytes.len() {
+            log.truncate(complete as u64)?;
+        }
+
+        Ok(Self { inner, log, next_seq, poisoned: false })
+    }
+
+    /// Log `delta` durably, then accumulate it
+    ///
+    /// The record is written and fsync'd before the manager is touched.
+    /// On error the delta is not applied and the log is truncated back to
+    /// its last complete record; if that truncation also fails the
+    /// wrapper is poisoned and every later call returns an error, since
+    /// the log can no longer be appended to consistently.
+    pub fn accumulate(&mut self, delta: u64) -> io::Result<()> {
+        if self.poisoned {
+            return Err(io::Error::other("delta log is poisoned by an earlier failed append"));
+        }
+        let mut record = [0u8; RECORD_BYTES];
+        record[..8].copy_from_slice(&self.next_seq.to_le_bytes());
+        record[8..].copy_from_slice(&delta.to_le_bytes());
+        if let Err(err) = self.log.write_all(&record).and_then(|()| self.log.sync_data()) {
+            if self.log.truncate(self.next_seq * RECORD_BYTES as u64).is_err() {
+                self.poisoned = true;
+            }
+            return Err(err);
+        }
+        self.next_seq += 1;
+        self.inner.accumulate(delta);
+        Ok(())
+    }
+
+    /// Reconstruct current state (READ operation)
+    pub fn reconstruct(&self) -> u64 {
+        self.inner.reconstruct()
+    }
+
+    /// Get the number of deltas recorded in the log
+    pub fn logged_count(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Get the wrapped manager
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Check whether a failed append left the log unusable
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    /// Close the log and unwrap the manager
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
@@ -2,4 +2,4 @@
 
 pub mod imu_fusion;
 
-pub use imu_fusion::IMUFusion;
+pub use imu_fusion::{IMUFusion, IMUFusionBuilder};
@@ -0,0 +1,175 @@
+//! Error types shared by the delta-state managers
+
+use std::fmt;
+use std::num::ParseIntError;
+
+/// Errors returned by fallible delta-state operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaError {
+    /// Bit range `[lo, hi)` is empty or exceeds the state width
+    InvalidBitRange {
+        /// Lower bound (inclusive)
+        lo: u32,
+        /// Upper bound (exclusive)
+        hi: u32,
+        /// State width in bits
+        width: u32,
+    },
+    /// Delta would make a monotonic state decrease
+    NonMonotonic,
+    /// Seek offset is past the end of the recorded deltas
+    SeekOutOfRange {
+        /// Requested number of deltas to apply
+        offset: usize,
+        /// Number of deltas available
+        available: usize,
+    },
+    /// A frame delta does not match the keyframe size
+    FrameSizeMismatch {
+        /// Index of the offending delta
+        index: usize,
+        /// Keyframe size in bytes
+        expected: usize,
+        /// Delta size in bytes
+        actual: usize,
+    },
+    /// Manager built with `strict_load` was accumulated into before `load`
+    NotLoaded,
+    /// The manager is frozen
+    Frozen,
+    /// A delta passed to `accumulate_checked` touched non-writable bits
+    MaskViolation {
+        /// Bits set in the delta but outside the writable mask
+        reserved_bits: u128,
+    },
+    /// Two declared bit fields share at least one bit
+    OverlappingFields {
+        /// Index of the earlier field
+        first: usize,
+        /// Index of the field overlapping it
+        second: usize,
+    },
+}
+
+impl fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeltaError::InvalidBitRange { lo, hi, width } => write!(
+                f,
+                "invalid bit range [{}, {}) for {}-bit state",
+                lo, hi, width
+            ),
+            DeltaError::NonMonotonic => write!(f, "delta would decrease monotonic state"),
+            DeltaError::SeekOutOfRange { offset, available } => write!(
+                f,
+                "seek offset {} is past the {} recorded deltas",
+                offset, available
+            ),
+            DeltaError::FrameSizeMismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "frame delta {} is {} bytes, expected {}",
+                index, actual, expected
+            ),
+            DeltaError::NotLoaded => write!(f, "accumulate before load on a strict_load manager"),
+            DeltaError::Frozen => write!(f, "manager is frozen"),
+            DeltaError::MaskViolation { reserved_bits } => {
+                write!(f, "delta touches reserved bits {:#x}", reserved_bits)
+            }
+            DeltaError::OverlappingFields { first, second } => {
+                write!(f, "bit fields {} and {} overlap", first, second)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeltaError {}
+
+/// Reasons a delta batch was rejected by `try_accumulate_batch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchError {
+    /// The manager is frozen
+    Frozen,
+    /// Manager built with `strict_load` was accumulated into before `load`
+    NotLoaded,
+    /// The delta at `index` would evict an earlier delta of the same batch,
+    /// so the batch could not be rolled back as a unit
+    HistoryOverflow {
+        /// Index of the first delta that does not fit
+        index: usize,
+        /// Number of history entries available to the batch
+        capacity: usize,
+    },
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Frozen => write!(f, "manager is frozen"),
+            BatchError::NotLoaded => write!(f, "accumulate before load on a strict_load manager"),
+            BatchError::HistoryOverflow { index, capacity } => write!(
+                f,
+                "batch delta {} exceeds history capacity of {} entries",
+                index, capacity
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// A state string passed to `from_hex_state` is not valid hex
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Underlying integer parse failure
+    pub source: ParseIntError,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid hex state: {}", self.source)
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Errors returned by the versioned bincode codec
+#[cfg(feature = "bincode")]
+#[derive(Debug)]
+pub enum BincodeError {
+    /// Input was empty, so no version byte was found
+    MissingVersion,
+    /// Version byte does not match a supported format
+    UnsupportedVersion(u8),
+    /// Payload failed to encode
+    Encode(bincode::error::EncodeError),
+    /// Payload failed to decode
+    Decode(bincode::error::DecodeError),
+    /// Payload decoded but describes an inconsistent manager
+    Invalid(&'static str),
+}
+
+#[cfg(feature = "bincode")]
+impl fmt::Display for BincodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BincodeError::MissingVersion => write!(f, "missing bincode version byte"),
+            BincodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported bincode format version {}", version)
+            }
+            BincodeError::Encode(err) => write!(f, "bincode encode failed: {}", err),
+            BincodeError::Decode(err) => write!(f, "bincode decode failed: {}", err),
+            BincodeError::Invalid(reason) => write!(f, "invalid bincode payload: {}", reason),
+        }
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl std::error::Error for BincodeError {}
@@ -0,0 +1,17 @@
+//! Structured records of applied deltas
+
+use std::time::SystemTime;
+
+/// Audit record returned by a manager's `accumulate_event`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppliedEvent<S> {
+    /// Position of the delta among all deltas the manager has accumulated
+    pub seq: u64,
+    /// Delta that was applied
+    pub delta: S,
+    /// State reconstructed right after applying the delta
+    pub resulting_state: S,
+    /// Wall-clock time the delta was applied
+    pub timestamp: SystemTime,
+}
@@ -0,0 +1,207 @@
+//! Packed field layouts over a delta-state manager
+//!
+//! The [`delta_fields!`](crate::delta_fields) macro declares named bit
+//! fields packed into a manager's state and generates typed accessors
+//! for them. Setters go through the manager's ACCUMULATE operation, so
+//! every field write is an ordinary XOR delta that can be rolled back.
+
+/// Declare packed bit fields over a delta-state manager
+///
+/// Generates a wrapper struct around the manager with one getter and one
+/// setter per field, plus a `for_each_field` visitor. Fields are packed
+/// LSB-first in declaration order. Each entry is written
+/// `getter, setter: Type = width`:
+///
+/// ```
+/// use atomik_video_streaming::{delta_fields, PriceTick};
+///
+/// delta_fields! {
+///     /// Quote fields packed into a PriceTick state
+///     pub struct Quote(PriceTick: u64) {
+///         bid, set_bid: u16 = 16,
+///         ask, set_ask: u16 = 16,
+///         size, set_size: u32 = 32,
+///     }
+/// }
+///
+/// let mut quote = Quote::new(PriceTick::new());
+/// quote.set_ask(101);
+/// quote.set_bid(99);
+/// assert_eq!((quote.bid(), quote.ask(), quote.size()), (99, 101, 0));
+///
+/// // Each setter is one delta in history
+/// quote.manager_mut().rollback(1);
+/// assert_eq!(quote.bid(), 0);
+/// ```
+///
+/// Widths that do not fit the state type are rejected at compile time:
+///
+/// ```compile_fail
+/// use atomik_video_streaming::{delta_fields, PriceTick};
+///
+/// delta_fields! {
+///     pub struct TooWide(PriceTick: u64) {
+///         a, set_a: u64 = 40,
+///         b, set_b: u64 = 40,
+///     }
+/// }
+/// ```
+///
+/// So are fields wider than their getter type, which would truncate:
+///
+/// ```compile_fail
+/// use atomik_video_streaming::{delta_fields, PriceTick};
+///
+/// delta_fields! {
+///     pub struct Truncated(PriceTick: u64) {
+///         bid, set_bid: u8 = 16,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! delta_fields {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident($manager:ty: $state:ty) {
+            $($getter:ident, $setter:ident: $fty:ty = $width:expr),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default)]
+        $vis struct $name {
+            manager: $manager,
+        }
+
+        const _: () = assert!(
+            0 $(+ $width)+ <= <$state>::BITS,
+            concat!("delta_fields!: field widths of ", stringify!($name), " overflow ", stringify!($state)),
+        );
+        $(
+            const _: () = assert!(
+                $width > 0,
+                concat!("delta_fields!: field ", stringify!($getter), " has zero width"),
+            );
+            const _: () = assert!(
+                $width <= <$fty>::BITS,
+                concat!("delta_fields!: field ", stringify!($getter), " is wider than its type"),
+            );
+        )+
+
+        impl $name {
+            /// Wrap a manager with this field layout
+            pub fn new(manager: $manager) -> Self {
+                Self { manager }
+            }
+
+            /// Get the underlying manager
+            pub fn manager(&self) -> &$manager {
+                &self.manager
+            }
+
+            /// Get the underlying manager mutably
+            pub fn manager_mut(&mut self) -> &mut $manager {
+                &mut self.manager
+            }
+
+            /// Unwrap the underlying manager
+            pub fn into_inner(self) -> $manager {
+                self.manager
+            }
+
+            /// Visit every field in declaration order with its name and value
+            ///
+            /// Values are widened to the manager's state type, which allows
+            /// generic hashing or serialization over the whole layout.
+            pub fn for_each_field(&self, mut f: impl FnMut(&str, $state)) {
+                $( f(stringify!($getter), self.$getter() as $state); )+
+            }
+
+            $crate::delta_fields!(@accessors $state; 0; $($getter, $setter: $fty = $width),+);
+        }
+    };
+
+    (@accessors $state:ty; $offset:expr; $getter:ident, $setter:ident: $fty:ty = $width:expr
+        $(, $rest_getter:ident, $rest_setter:ident: $rest_ty:ty = $rest_width:expr)*) => {
+        #[doc = concat!("Read the `", stringify!($getter), "` field from the reconstructed state")]
+        pub fn $getter(&self) -> $fty {
+            let mask = <$state>::MAX >> (<$state>::BITS - ($width));
+            ((self.manager.reconstruct() >> ($offset)) & mask) as $fty
+        }
+
+        #[doc = concat!("Write the `", stringify!($getter), "` field as a single XOR delta")]
+        ///
+        /// Bits of `value` beyond the field width are discarded.
+        pub fn $setter(&mut self, value: $fty) {
+            let mask = <$state>::MAX >> (<$state>::BITS - ($width));
+            let current = (self.manager.reconstruct() >> ($offset)) & mask;
+            let target = (value as $state) & mask;
+            self.manager.accumulate((current ^ target) << ($offset));
+        }
+
+        $crate::delta_fields!(@accessors $state; $offset + $width;
+            $($rest_getter, $rest_setter: $rest_ty = $rest_width),*);
+    };
+
+    (@accessors $state:ty; $offset:expr;) => {};
+}
+
+/// Unpack a manager's raw state bits into a typed value
+///
+/// Implemented by user structs that mirror a packed layout, so
+/// `reconstruct_as` can hand back typed state instead of raw bits. The
+/// same-width integers, `f64` (for `u64` states) and little-endian byte
+/// arrays are provided, all as bit-exact reinterpretations:
+///
+/// ```
+/// use atomik_video_streaming::{FromBits, PriceTick};
+///
+/// struct Spread {
+///     bid: u32,
+///     ask: u32,
+/// }
+///
+/// impl FromBits<u64> for Spread {
+///     fn from_bits(bits: u64) -> Self {
+///         Spread {
+///             bid: bits as u32,
+///             ask: (bits >> 32) as u32,
+///         }
+///     }
+/// }
+///
+/// let manager = PriceTick::from_state(101 << 32 | 99);
+/// let spread: Spread = manager.reconstruct_as();
+/// assert_eq!((spread.bid, spread.ask), (99, 101));
+/// ```
+pub trait FromBits<S> {
+    /// Build the value from packed state bits
+    fn from_bits(bits: S) -> Self;
+}
+
+/// Bit-exact reinterpretation of the raw state as primitive types
+macro_rules! from_bits_primitive {
+    ($state:ty => $($target:ty: |$bits:ident| $convert:expr),+ $(,)?) => {
+        $(
+            impl FromBits<$state> for $target {
+                fn from_bits($bits: $state) -> Self {
+                    $convert
+                }
+            }
+        )+
+    };
+}
+
+from_bits_primitive! {
+    u64 =>
+    u64: |bits| bits,
+    i64: |bits| bits as i64,
+    f64: |bits| f64::from_bits(bits),
+    [u8; 8]: |bits| bits.to_le_bytes(),
+}
+
+from_bits_primitive! {
+    u128 =>
+    u128: |bits| bits,
+    i128: |bits| bits as i128,
+    [u8; 16]: |bits| bits.to_le_bytes(),
+}
@@ -2,4 +2,7 @@
 
 pub mod price_tick;
 
-pub use price_tick::PriceTick;
+#[cfg(feature = "python")]
+pub mod python;
+
+pub use price_tick::{PriceTick, PriceTickBuilder};
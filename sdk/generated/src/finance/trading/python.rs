@@ -0,0 +1,69 @@
+//! Python bindings for PriceTick
+//!
+//! Enabled with the `python` feature. Exposes `PriceTick` as a PyO3 class;
+//! u64 values map to Python `int`, and negative or oversized ints raise
+//! `OverflowError` at the call boundary. Build and install the extension
+//! module with `maturin develop` (or `maturin build`) from the crate
+//! root, which picks up the settings in `pyproject.toml`.
+//!
+//! ```python
+//! from atomik_trading import PriceTick
+//!
+//! tick = PriceTick()
+//! tick.load(0xAAAA)
+//! tick.accumulate(0x5555)
+//! assert tick.reconstruct() == 0xFFFF
+//! assert tick.rollback(1) == 1
+//! assert tick.history_size() == 0
+//! ```
+
+use pyo3::prelude::*;
+
+use super::PriceTick;
+
+/// PriceTick delta-state manager exposed to Python
+#[pyclass(name = "PriceTick", skip_from_py_object)]
+#[derive(Debug, Clone, Default)]
+pub struct PyPriceTick {
+    inner: PriceTick,
+}
+
+#[pymethods]
+impl PyPriceTick {
+    /// Create a new delta-state manager
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load initial state (LOAD operation)
+    pub fn load(&mut self, initial_state: u64) {
+        self.inner.load(initial_state);
+    }
+
+    /// Accumulate delta (ACCUMULATE operation)
+    pub fn accumulate(&mut self, delta: u64) {
+        self.inner.accumulate(delta);
+    }
+
+    /// Reconstruct current state (READ operation)
+    pub fn reconstruct(&self) -> u64 {
+        self.inner.reconstruct()
+    }
+
+    /// Rollback the last N delta operations
+    pub fn rollback(&mut self, count: usize) -> usize {
+        self.inner.rollback(count)
+    }
+
+    /// Get the number of deltas in history
+    pub fn history_size(&self) -> usize {
+        self.inner.history_size()
+    }
+}
+
+/// Python module registration
+#[pymodule]
+pub fn atomik_trading(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPriceTick>()
+}
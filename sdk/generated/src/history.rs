@@ -0,0 +1,183 @@
+//! Pluggable storage for delta history
+//!
+//! Managers keep their rollback history behind the [`HistoryStore`]
+//! trait. [`InMemoryStore`] (a `VecDeque`) is the default; other
+//! backends such as memory-mapped files or databases can implement the
+//! trait and be passed to a manager's `with_store` constructor.
+
+use std::collections::VecDeque;
+
+/// Ordered delta history, oldest first
+pub trait HistoryStore<T> {
+    /// Iterator over retained deltas, oldest first
+    type Iter<'a>: DoubleEndedIterator<Item = &'a T> + ExactSizeIterator
+    where
+        Self: 'a,
+        T: 'a;
+
+    /// Append a delta as the newest entry
+    fn push(&mut self, delta: T);
+
+    /// Remove and return the newest delta
+    fn pop_back(&mut self) -> Option<T>;
+
+    /// Remove and return the oldest delta
+    fn pop_front(&mut self) -> Option<T>;
+
+    /// Get the number of retained deltas
+    fn len(&self) -> usize;
+
+    /// Iterate over retained deltas, oldest first
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Check if no deltas are retained
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every delta
+    fn clear(&mut self) {
+        while self.pop_back().is_some() {}
+    }
+
+    /// Drop the newest deltas until at most `len` remain
+    fn truncate(&mut self, len: usize) {
+        while self.len() > len {
+            self.pop_back();
+        }
+    }
+
+    /// Remove and return the delta at `index` (0 = oldest)
+    ///
+    /// The provided implementation pops the newer entries aside and pushes
+    /// them back, which is O(n) like `VecDeque::remove`.
+    fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut newer = Vec::with_capacity(self.len() - index - 1);
+        while self.len() > index + 1 {
+            newer.extend(self.pop_back());
+        }
+        let removed = self.pop_back();
+        while let Some(delta) = newer.pop() {
+            self.push(delta);
+        }
+        removed
+    }
+
+    /// Create an empty store with the same settings as this one
+    ///
+    /// The provided implementation returns `Self::default()`; stores with
+    /// their own configuration should override it to keep that configuration.
+    fn empty_like(&self) -> Self
+    where
+        Self: Default,
+    {
+        Self::default()
+    }
+}
+
+/// Default in-memory history backed by a `VecDeque`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InMemoryStore<T> {
+    deltas: VecDeque<T>,
+    /// Fixed growth increment and the capacity it stops at, if set
+    #[cfg_attr(feature = "serde", serde(skip))]
+    growth: Option<(usize, usize)>,
+}
+
+impl<T> InMemoryStore<T> {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self {
+            deltas: VecDeque::new(),
+            growth: None,
+        }
+    }
+
+    /// Create an empty store that grows `chunk` entries at a time
+    ///
+    /// Instead of doubling, a full store reserves exactly `chunk` more
+    /// entries, and never more than needed to hold `limit` in total.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk` is zero.
+    pub fn with_growth_chunk(chunk: usize, limit: usize) -> Self {
+        assert!(chunk > 0, "InMemoryStore growth chunk must be non-zero");
+        Self {
+            deltas: VecDeque::new(),
+            growth: Some((chunk, limit)),
+        }
+    }
+
+    /// Get the number of deltas the store can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.deltas.capacity()
+    }
+
+    /// Release spare capacity back to the allocator
+    pub fn shrink_to_fit(&mut self) {
+        self.deltas.shrink_to_fit();
+    }
+}
+
+impl<T> Default for InMemoryStore<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HistoryStore<T> for InMemoryStore<T> {
+    type Iter<'a>
+        = std::collections::vec_deque::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn push(&mut self, delta: T) {
+        if let Some((chunk, limit)) = self.growth {
+            let len = self.deltas.len();
+            if len == self.deltas.capacity() {
+                self.deltas.reserve_exact(chunk.min(limit.saturating_sub(len)).max(1));
+            }
+        }
+        self.deltas.push_back(delta);
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.deltas.pop_back()
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.deltas.pop_front()
+    }
+
+    fn len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.deltas.iter()
+    }
+
+    fn empty_like(&self) -> Self {
+        Self {
+            deltas: VecDeque::new(),
+            growth: self.growth,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.deltas.clear();
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.deltas.truncate(len);
+    }
+
+    fn remove(&mut self, index: usize) -> Option<T> {
+        self.deltas.remove(index)
+    }
+}
@@ -0,0 +1,70 @@
+//! Accumulate latency histogram for the `latency` feature
+
+use std::time::Duration;
+
+/// Linear sub-buckets per power of two (about 6% relative precision)
+const SUB_BUCKETS: u64 = 16;
+const SUB_BUCKET_BITS: u32 = SUB_BUCKETS.trailing_zeros();
+/// Buckets needed to cover every `u64` nanosecond value
+const BUCKETS: usize = ((u64::BITS - SUB_BUCKET_BITS + 1) as u64 * SUB_BUCKETS) as usize;
+
+/// HDR-style log-linear histogram of durations in nanoseconds
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LatencyHistogram {
+    /// Sample counts per bucket, allocated on first record
+    counts: Vec<u64>,
+    /// Total number of recorded samples
+    total: u64,
+}
+
+impl LatencyHistogram {
+    /// Record one sample
+    pub(crate) fn record(&mut self, elapsed: Duration) {
+        if self.counts.is_empty() {
+            self.counts = vec![0; BUCKETS];
+        }
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.counts[bucket_index(nanos)] += 1;
+        self.total += 1;
+    }
+
+    /// Get the duration at or below which `p` percent of samples fall
+    ///
+    /// Reports the upper bound of the matching bucket, or zero if nothing
+    /// has been recorded.
+    pub(crate) fn percentile(&self, p: f64) -> Duration {
+        if self.total == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((p.clamp(0.0, 100.0) / 100.0 * self.total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Duration::from_nanos(bucket_upper_bound(index));
+            }
+        }
+        Duration::from_nanos(u64::MAX)
+    }
+}
+
+fn bucket_index(nanos: u64) -> usize {
+    if nanos < SUB_BUCKETS {
+        return nanos as usize;
+    }
+    let exponent = u64::BITS - 1 - nanos.leading_zeros();
+    let shift = exponent - SUB_BUCKET_BITS;
+    let sub = (nanos >> shift) & (SUB_BUCKETS - 1);
+    ((shift + 1) as u64 * SUB_BUCKETS + sub) as usize
+}
+
+fn bucket_upper_bound(index: usize) -> u64 {
+    let index = index as u64;
+    if index < SUB_BUCKETS {
+        return index;
+    }
+    let shift = (index / SUB_BUCKETS - 1) as u32;
+    let sub = index % SUB_BUCKETS;
+    let lower = (SUB_BUCKETS + sub) << shift;
+    lower + ((1u64 << shift) - 1)
+}
@@ -1,9 +1,74 @@
 //! ATOMiK Rust SDK
 //!
 //! Delta-state computing primitives based on XOR algebra.
+//!
+//! # Lenient vs strict behavior
+//!
+//! By default, operations that ask for more than the manager holds are
+//! lenient and silently capped. Enabling the `strict` feature turns them
+//! into panics, for builds that want such misuse to fail loudly.
+//!
+//! | Operation                                    | default          | `strict`         |
+//! |----------------------------------------------|------------------|------------------|
+//! | `rollback(n)` with `n` above history         | rolls back all   | panics           |
+//! | `rollback_collect(n)` with `n` above history | rolls back all   | panics           |
+//! | `rollback_to_tag` with an unknown tag        | returns 0        | panics           |
+//! | `rollback_to_last_commit` without a marker   | returns 0        | panics           |
+//! | `rollback_field(i, n)` past field history    | rolls back all   | panics           |
+//! | `rollback(n)` on a manager without history   | returns 0        | returns 0        |
+//! | infallible mutation while frozen             | panics           | panics           |
+//! | `Result`-returning mutation while frozen     | returns `Frozen` | returns `Frozen` |
+//!
+//! Freezing blocks changes to state, history and commit markers. Settings
+//! that only shape future deltas (`pause_history` / `resume_history`,
+//! `enable_delta_stats` / `disable_delta_stats`, `set_pressure_alarm` /
+//! `clear_pressure_alarm`) still work while frozen.
+
+pub mod batcher;
+pub mod bitplane;
+pub mod check;
+mod delta_state;
+pub mod durable;
+pub mod error;
+pub mod event;
+pub mod fields;
+mod fingerprint;
+pub mod history;
+#[cfg(feature = "latency")]
+mod latency;
+pub mod manager;
+pub mod monotonic;
+pub mod ratelimit;
+pub mod sketch;
+pub mod stats;
+
+pub mod edge {
+    pub mod sensor;
+}
+
+pub mod finance {
+    pub mod trading;
+}
 
 pub mod video {
     pub mod streaming;
 }
 
-pub use video::streaming::H264Delta;
+pub use batcher::TickBatcher;
+pub use bitplane::BitPlaneDeltaState;
+pub use check::{Op, SequenceMismatch};
+pub use durable::{DeltaLog, DurableDeltaState};
+pub use error::{BatchError, DeltaError, ParseError};
+pub use event::AppliedEvent;
+pub use fields::FromBits;
+pub use history::{HistoryStore, InMemoryStore};
+pub use manager::DeltaManager;
+pub use monotonic::MonotonicDeltaState;
+pub use ratelimit::{Clock, RateLimitedDeltaState, SystemClock};
+pub use sketch::DeltaSketch;
+pub use stats::DeltaStats;
+#[cfg(feature = "bincode")]
+pub use error::BincodeError;
+pub use edge::sensor::{IMUFusion, IMUFusionBuilder};
+pub use finance::trading::{PriceTick, PriceTickBuilder};
+pub use video::streaming::{H264Delta, H264DeltaBuilder};
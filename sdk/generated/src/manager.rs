@@ -0,0 +1,36 @@
+//! Common interface over the generated delta-state managers
+
+use crate::error::DeltaError;
+
+/// Shared LOAD / ACCUMULATE / READ / ROLLBACK shape of every manager
+///
+/// Lets generic code drive any generated manager regardless of its
+/// state width.
+pub trait DeltaManager {
+    /// Packed state and delta type
+    type State: Copy;
+
+    /// Load initial state (LOAD operation)
+    fn load(&mut self, initial_state: Self::State);
+
+    /// Accumulate delta (ACCUMULATE operation)
+    fn accumulate(&mut self, delta: Self::State);
+
+    /// Accumulate delta, returning an error instead of panicking
+    ///
+    /// Managers that can refuse a delta (frozen, or unloaded with
+    /// `strict_load`) override this; the default always accepts.
+    fn try_accumulate(&mut self, delta: Self::State) -> Result<(), DeltaError> {
+        self.accumulate(delta);
+        Ok(())
+    }
+
+    /// Reconstruct current state (READ operation)
+    fn reconstruct(&self) -> Self::State;
+
+    /// Rollback the last N delta operations, returning how many were undone
+    fn rollback(&mut self, count: usize) -> usize;
+
+    /// Get the number of deltas in history
+    fn history_size(&self) -> usize;
+}
@@ -0,0 +1,61 @@
+//! Monotonic constraint layered over a delta-state manager
+
+use std::ops::BitXor;
+
+use crate::error::DeltaError;
+use crate::manager::DeltaManager;
+
+/// Wrapper that only accepts deltas keeping the state non-decreasing
+///
+/// The XOR engine itself has no notion of order; this wrapper compares
+/// the state a delta would produce against the current one and rejects
+/// it, leaving the manager untouched, if the state would go down.
+#[derive(Debug, Clone, Default)]
+pub struct MonotonicDeltaState<M> {
+    inner: M,
+}
+
+impl<M> MonotonicDeltaState<M>
+where
+    M: DeltaManager,
+    M::State: Ord + BitXor<Output = M::State>,
+{
+    /// Wrap a manager, taking its current state as the starting point
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+
+    /// Load initial state (LOAD operation)
+    pub fn load(&mut self, initial_state: M::State) {
+        self.inner.load(initial_state);
+    }
+
+    /// Accumulate delta if the resulting state does not decrease
+    ///
+    /// Returns `Err(DeltaError::NonMonotonic)` without applying the delta
+    /// if `reconstruct() ^ delta` would be smaller than `reconstruct()`.
+    /// Errors from the wrapped manager's `try_accumulate`, such as
+    /// `Frozen` or `NotLoaded`, are passed through.
+    pub fn accumulate(&mut self, delta: M::State) -> Result<(), DeltaError> {
+        let current = self.inner.reconstruct();
+        if current ^ delta < current {
+            return Err(DeltaError::NonMonotonic);
+        }
+        self.inner.try_accumulate(delta)
+    }
+
+    /// Reconstruct current state (READ operation)
+    pub fn reconstruct(&self) -> M::State {
+        self.inner.reconstruct()
+    }
+
+    /// Get the wrapped manager
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Unwrap the manager, dropping the monotonic constraint
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
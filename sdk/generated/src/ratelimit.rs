@@ -0,0 +1,118 @@
+//! Minimum-interval delta coalescing over a delta-state manager
+
+use std::ops::BitXor;
+use std::time::{Duration, Instant};
+
+use crate::manager::DeltaManager;
+
+/// Source of the current time for [`RateLimitedDeltaState`]
+///
+/// Lets tests drive the wrapper with a mocked clock.
+pub trait Clock {
+    /// Get the current instant
+    fn now(&self) -> Instant;
+}
+
+/// [`Clock`] backed by `Instant::now`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Wrapper that commits at most one combined delta per `min_interval`
+///
+/// Deltas pushed before the interval since the last commit has elapsed
+/// are XORed into a buffer. The first push after it has elapsed folds the
+/// buffer into the manager as a single history entry.
+#[derive(Debug, Clone)]
+pub struct RateLimitedDeltaState<M: DeltaManager, C = SystemClock> {
+    inner: M,
+    clock: C,
+    min_interval: Duration,
+    last_commit: Instant,
+    pending: M::State,
+    pending_count: usize,
+}
+
+impl<M> RateLimitedDeltaState<M>
+where
+    M: DeltaManager,
+    M::State: Default + BitXor<Output = M::State>,
+{
+    /// Wrap a manager, timing commits with the system clock
+    pub fn new(inner: M, min_interval: Duration) -> Self {
+        Self::with_clock(inner, min_interval, SystemClock)
+    }
+}
+
+impl<M, C> RateLimitedDeltaState<M, C>
+where
+    M: DeltaManager,
+    M::State: Default + BitXor<Output = M::State>,
+    C: Clock,
+{
+    /// Wrap a manager, timing commits with `clock`
+    ///
+    /// The interval is measured from construction until the first commit.
+    pub fn with_clock(inner: M, min_interval: Duration, clock: C) -> Self {
+        Self {
+            last_commit: clock.now(),
+            inner,
+            clock,
+            min_interval,
+            pending: M::State::default(),
+            pending_count: 0,
+        }
+    }
+
+    /// Buffer a delta, committing the buffer if the interval has elapsed
+    ///
+    /// Returns whether a combined delta was committed to the manager.
+    pub fn push(&mut self, delta: M::State) -> bool {
+        self.pending = self.pending ^ delta;
+        self.pending_count += 1;
+        let now = self.clock.now();
+        if now.duration_since(self.last_commit) < self.min_interval {
+            return false;
+        }
+        self.commit(now);
+        true
+    }
+
+    /// Commit any buffered deltas now, regardless of the interval
+    ///
+    /// Returns whether anything was committed.
+    pub fn flush(&mut self) -> bool {
+        if self.pending_count == 0 {
+            return false;
+        }
+        let now = self.clock.now();
+        self.commit(now);
+        true
+    }
+
+    fn commit(&mut self, now: Instant) {
+        self.inner.accumulate(std::mem::take(&mut self.pending));
+        self.pending_count = 0;
+        self.last_commit = now;
+    }
+
+    /// Get the number of deltas buffered since the last commit
+    pub fn pending_count(&self) -> usize {
+        self.pending_count
+    }
+
+    /// Get the wrapped manager
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Unwrap the manager, discarding any buffered deltas
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
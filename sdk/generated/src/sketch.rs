@@ -0,0 +1,131 @@
+//! Invertible sketches for reconciling delta sets between nodes
+
+use crate::fingerprint::fnv1a_64;
+
+/// Cells each delta is hashed into, one per sub-table
+const HASHES: usize = 3;
+
+/// One cell of the sketch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Cell {
+    /// Number of deltas inserted minus removed
+    count: i64,
+    /// XOR of the deltas in the cell
+    delta_sum: u64,
+    /// XOR of the deltas' check hashes, to recognise single-delta cells
+    check_sum: u64,
+}
+
+/// Invertible Bloom lookup table over a set of deltas
+///
+/// Each node inserts its delta history into a sketch of the same size
+/// and exchanges only the sketch. Because XOR is self-inverse, deltas
+/// held by both nodes cancel when the sketches are subtracted, and `diff`
+/// peels the few that remain. A sketch decodes reliably when it has
+/// roughly twice as many cells as the expected difference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeltaSketch {
+    cells: Vec<Cell>,
+}
+
+impl DeltaSketch {
+    /// Create an empty sketch with about `cells` cells
+    ///
+    /// The count is rounded up to a multiple of the hash count so every
+    /// sub-table has the same size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cells` is zero.
+    pub fn new(cells: usize) -> Self {
+        assert!(cells > 0, "DeltaSketch needs at least one cell");
+        Self {
+            cells: vec![Cell::default(); cells.div_ceil(HASHES) * HASHES],
+        }
+    }
+
+    /// Build a sketch of `cells` cells from a delta history
+    pub fn from_deltas(cells: usize, deltas: impl IntoIterator<Item = u64>) -> Self {
+        let mut sketch = Self::new(cells);
+        for delta in deltas {
+            sketch.insert(delta);
+        }
+        sketch
+    }
+
+    /// Add a delta to the sketch
+    pub fn insert(&mut self, delta: u64) {
+        self.toggle(delta, 1);
+    }
+
+    /// Get the number of cells
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Recover the deltas held by exactly one of the two sketches
+    ///
+    /// Returns the symmetric difference in no particular order. If the
+    /// difference is too large for the sketch size, peeling stalls and the
+    /// result is incomplete.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sketches have different cell counts.
+    pub fn diff(&self, other: &DeltaSketch) -> Vec<u64> {
+        assert_eq!(
+            self.cells.len(),
+            other.cells.len(),
+            "DeltaSketch diff needs sketches of equal size"
+        );
+        let mut residual = DeltaSketch {
+            cells: self
+                .cells
+                .iter()
+                .zip(&other.cells)
+                .map(|(mine, theirs)| Cell {
+                    count: mine.count - theirs.count,
+                    delta_sum: mine.delta_sum ^ theirs.delta_sum,
+                    check_sum: mine.check_sum ^ theirs.check_sum,
+                })
+                .collect(),
+        };
+
+        let mut recovered = Vec::new();
+        while let Some(cell) = residual.cells.iter().find(|cell| cell.is_pure()).copied() {
+            recovered.push(cell.delta_sum);
+            residual.toggle(cell.delta_sum, -cell.count);
+        }
+        recovered
+    }
+
+    fn toggle(&mut self, delta: u64, count: i64) {
+        let check = check_hash(delta);
+        let width = self.cells.len() / HASHES;
+        for seed in 0..HASHES {
+            let index = seed * width + (cell_hash(delta, seed) % width as u64) as usize;
+            let cell = &mut self.cells[index];
+            cell.count += count;
+            cell.delta_sum ^= delta;
+            cell.check_sum ^= check;
+        }
+    }
+}
+
+impl Cell {
+    /// Whether the cell holds exactly one delta from one side
+    fn is_pure(&self) -> bool {
+        (self.count == 1 || self.count == -1) && self.check_sum == check_hash(self.delta_sum)
+    }
+}
+
+fn cell_hash(delta: u64, seed: usize) -> u64 {
+    let mut bytes = [0u8; 9];
+    bytes[0] = seed as u8;
+    bytes[1..].copy_from_slice(&delta.to_le_bytes());
+    fnv1a_64(&bytes)
+}
+
+fn check_hash(delta: u64) -> u64 {
+    cell_hash(delta, HASHES)
+}
@@ -0,0 +1,114 @@
+//! Rolling statistics over recently accumulated deltas
+
+use std::collections::VecDeque;
+
+/// Rolling min / max / mean over the last `window` deltas
+///
+/// Each delta is interpreted as an unsigned value. All statistics are
+/// maintained incrementally: `push` is amortized O(1), and `min`, `max`
+/// and `mean` are O(1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeltaStats<T> {
+    /// Values currently inside the window, oldest first
+    values: VecDeque<T>,
+    /// Candidates for the minimum as (sequence, value), increasing by value
+    min_queue: VecDeque<(u64, T)>,
+    /// Candidates for the maximum as (sequence, value), decreasing by value
+    max_queue: VecDeque<(u64, T)>,
+    /// Exact sum of the window, modulo 2^128
+    sum: u128,
+    /// Number of values ever pushed (sequence of the next value)
+    pushed: u64,
+    /// Window size
+    window: usize,
+}
+
+impl<T: Copy + Ord + Into<u128>> DeltaStats<T> {
+    /// Create an empty aggregator over the last `window` values
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero.
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "DeltaStats window must be non-zero");
+        Self {
+            values: VecDeque::with_capacity(window),
+            min_queue: VecDeque::new(),
+            max_queue: VecDeque::new(),
+            sum: 0,
+            pushed: 0,
+            window,
+        }
+    }
+
+    /// Add a value, evicting the oldest one once the window is full
+    pub fn push(&mut self, value: T) {
+        if self.values.len() == self.window {
+            if let Some(evicted) = self.values.pop_front() {
+                self.sum = self.sum.wrapping_sub(evicted.into());
+            }
+            let oldest = self.pushed + 1 - self.window as u64;
+            while self.min_queue.front().is_some_and(|&(seq, _)| seq < oldest) {
+                self.min_queue.pop_front();
+            }
+            while self.max_queue.front().is_some_and(|&(seq, _)| seq < oldest) {
+                self.max_queue.pop_front();
+            }
+        }
+
+        while self.min_queue.back().is_some_and(|&(_, v)| v >= value) {
+            self.min_queue.pop_back();
+        }
+        self.min_queue.push_back((self.pushed, value));
+        while self.max_queue.back().is_some_and(|&(_, v)| v <= value) {
+            self.max_queue.pop_back();
+        }
+        self.max_queue.push_back((self.pushed, value));
+
+        self.values.push_back(value);
+        self.sum = self.sum.wrapping_add(value.into());
+        self.pushed += 1;
+    }
+
+    /// Get the smallest value in the window
+    pub fn min(&self) -> Option<T> {
+        self.min_queue.front().map(|&(_, v)| v)
+    }
+
+    /// Get the largest value in the window
+    pub fn max(&self) -> Option<T> {
+        self.max_queue.front().map(|&(_, v)| v)
+    }
+
+    /// Get the arithmetic mean of the window
+    ///
+    /// Exact as long as the window sum fits in a u128.
+    pub fn mean(&self) -> Option<f64> {
+        if self.values.is_empty() {
+            None
+        } else {
+            Some(self.sum as f64 / self.values.len() as f64)
+        }
+    }
+
+    /// Get the number of values currently in the window
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if the window holds no values
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get the window size
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Drop all values, keeping the window size
+    pub fn clear(&mut self) {
+        *self = Self::new(self.window);
+    }
+}
@@ -1,5 +1,6 @@
 //! Streaming module
 
 pub mod h264_delta;
+mod seek;
 
-pub use h264_delta::H264Delta;
+pub use h264_delta::{H264Delta, H264DeltaBuilder};
@@ -0,0 +1,39 @@
+//! Seeking within recorded H264Delta frame streams
+
+use super::H264Delta;
+use crate::error::DeltaError;
+
+impl H264Delta {
+    /// Reconstruct frame `k` of a recorded stream from its keyframe
+    ///
+    /// XOR-folds only the first `k` frame deltas onto `keyframe`, so
+    /// `k == 0` returns the keyframe itself and `k == deltas.len()` the
+    /// last frame. This is the byte-frame analog of rolling a manager
+    /// forward to a given index.
+    pub fn reconstruct_at_offset(
+        keyframe: &[u8],
+        deltas: &[&[u8]],
+        k: usize,
+    ) -> Result<Vec<u8>, DeltaError> {
+        if k > deltas.len() {
+            return Err(DeltaError::SeekOutOfRange {
+                offset: k,
+                available: deltas.len(),
+            });
+        }
+        let mut frame = keyframe.to_vec();
+        for (index, delta) in deltas[..k].iter().enumerate() {
+            if delta.len() != frame.len() {
+                return Err(DeltaError::FrameSizeMismatch {
+                    index,
+                    expected: frame.len(),
+                    actual: delta.len(),
+                });
+            }
+            for (byte, d) in frame.iter_mut().zip(delta.iter()) {
+                *byte ^= d;
+            }
+        }
+        Ok(frame)
+    }
+}
@@ -0,0 +1,37 @@
+//! Integration tests for BitPlaneDeltaState
+
+use atomik_video_streaming::{BitPlaneDeltaState, DeltaError};
+
+#[test]
+fn test_rollback_one_field_only() {
+    let mut state = BitPlaneDeltaState::new(&[(0, 16), (16, 32)]).unwrap();
+    state.load(0);
+    state.accumulate(0x0000_0001);
+    state.accumulate(0x0002_0000);
+    // Touches both fields; each records its own part
+    state.accumulate(0x0010_0010);
+    assert_eq!(state.reconstruct(), 0x0012_0011);
+    assert_eq!(state.field_history_size(0), 2);
+    assert_eq!(state.field_history_size(1), 2);
+
+    assert_eq!(state.rollback_field(1, 1), 1);
+    assert_eq!(state.reconstruct(), 0x0002_0011);
+    #[cfg(not(feature = "strict"))]
+    assert_eq!(state.rollback_field(1, 5), 1);
+    #[cfg(feature = "strict")]
+    assert_eq!(state.rollback_field(1, 1), 1);
+    assert_eq!(state.reconstruct(), 0x0000_0011);
+    assert_eq!(state.field_history_size(0), 2);
+}
+
+#[test]
+fn test_rejects_bad_fields() {
+    assert_eq!(
+        BitPlaneDeltaState::new(&[(8, 8)]).unwrap_err(),
+        DeltaError::InvalidBitRange { lo: 8, hi: 8, width: 64 }
+    );
+    assert_eq!(
+        BitPlaneDeltaState::new(&[(0, 16), (32, 40), (8, 24)]).unwrap_err(),
+        DeltaError::OverlappingFields { first: 0, second: 2 }
+    );
+}
@@ -0,0 +1,1269 @@
+//! Shared integration tests for the delta-state managers
+//!
+//! Each manager's test file expands `manager_tests!` with its own type,
+//! so every manager runs the same suite.
+
+macro_rules! manager_tests {
+    ($name:ident, $state:ident) => {
+        use atomik_video_streaming::{BatchError, DeltaError, $name};
+
+        #[test]
+        fn test_load() {
+            let mut manager = $name::new();
+            manager.load(0x1234567890ABCDEF);
+            assert_eq!(manager.get_initial_state(), 0x1234567890ABCDEF);
+            assert_eq!(manager.get_accumulator(), 0);
+        }
+
+        #[test]
+        fn test_accumulate() {
+            let mut manager = $name::new();
+            manager.load(0);
+            manager.accumulate(0x1111111111111111);
+            assert_eq!(manager.get_accumulator(), 0x1111111111111111);
+            manager.accumulate(0x2222222222222222);
+            assert_eq!(manager.get_accumulator(), 0x3333333333333333);
+        }
+
+        #[test]
+        fn test_reconstruct() {
+            let mut manager = $name::new();
+            manager.load(0xAAAAAAAAAAAAAAAA);
+            manager.accumulate(0x5555555555555555);
+            // 0xAAAA XOR 0x5555 = 0xFFFF
+            assert_eq!(manager.reconstruct(), 0xFFFFFFFFFFFFFFFF);
+        }
+
+        #[test]
+        fn test_self_inverse() {
+            let mut manager = $name::new();
+            manager.load(0xAAAAAAAAAAAAAAAA);
+            let delta = 0x1234567890ABCDEF;
+            manager.accumulate(delta);
+            manager.accumulate(delta);  // Apply same delta twice
+            // Self-inverse: delta XOR delta = 0
+            assert!(manager.is_accumulator_zero());
+            assert_eq!(manager.reconstruct(), 0xAAAAAAAAAAAAAAAA);
+        }
+
+        #[test]
+        fn test_rollback() {
+            let mut manager = $name::new();
+            manager.load(0);
+            manager.accumulate(0x1111111111111111);
+            manager.accumulate(0x2222222222222222);
+            manager.accumulate(0x4444444444444444);
+            assert_eq!(manager.get_accumulator(), 0x7777777777777777);
+
+            // Rollback last 2 operations
+            let count = manager.rollback(2);
+            assert_eq!(count, 2);
+            assert_eq!(manager.get_accumulator(), 0x1111111111111111);
+        }
+
+        #[test]
+        fn test_from_state() {
+            let manager = $name::from_state(0xDEADBEEFCAFEF00D);
+            assert_eq!(manager.reconstruct(), 0xDEADBEEFCAFEF00D);
+            assert_eq!(manager.get_initial_state(), 0xDEADBEEFCAFEF00D);
+            assert!(manager.is_accumulator_zero());
+            assert_eq!(manager.history_size(), 0);
+        }
+
+        #[test]
+        fn test_rollback_to_tag() {
+            let mut manager = $name::new();
+            manager.load(0);
+            manager.accumulate_tagged(0x1, 100);
+            manager.accumulate_tagged(0x2, 200);
+            manager.accumulate(0x4);
+            manager.accumulate_tagged(0x8, 300);
+            assert_eq!(manager.get_accumulator(), 0xF);
+
+            // Removes the tag-200 delta and everything after it
+            assert_eq!(manager.rollback_to_tag(200), 3);
+            assert_eq!(manager.get_accumulator(), 0x1);
+            assert_eq!(manager.history_size(), 1);
+
+            // Unknown tags leave state untouched
+            #[cfg(not(feature = "strict"))]
+            assert_eq!(manager.rollback_to_tag(999), 0);
+            assert_eq!(manager.get_accumulator(), 0x1);
+        }
+
+        #[test]
+        fn test_iter_states() {
+            let mut manager = $name::new();
+            manager.load(0xF0);
+            manager.accumulate(0x01);
+            manager.accumulate(0x02);
+            manager.accumulate(0x04);
+
+            let states: Vec<_> = manager.iter_states().collect();
+            assert_eq!(states, vec![(0, 0xF1), (1, 0xF3), (2, 0xF7)]);
+            assert_eq!(states.last().unwrap().1, manager.reconstruct());
+        }
+
+        #[test]
+        fn test_rollback_collect() {
+            let mut manager = $name::new();
+            manager.load(0);
+            manager.accumulate(0x1111111111111111);
+            manager.accumulate(0x2222222222222222);
+            manager.accumulate(0x4444444444444444);
+
+            let removed = manager.rollback_collect(2);
+            assert_eq!(removed, vec![0x4444444444444444, 0x2222222222222222]);
+            assert_eq!(manager.get_accumulator(), 0x1111111111111111);
+            assert_eq!(manager.history_size(), 1);
+        }
+
+        #[test]
+        fn test_freeze_allows_reads() {
+            let mut manager = $name::new();
+            manager.load(0xAA);
+            manager.accumulate(0x0F);
+            manager.freeze();
+            assert!(manager.is_frozen());
+            assert_eq!(manager.reconstruct(), 0xA5);
+            assert_eq!(manager.history_size(), 1);
+
+            manager.unfreeze();
+            manager.accumulate(0x0F);
+            assert_eq!(manager.reconstruct(), 0xAA);
+            assert_eq!(manager.rollback(1), 1);
+        }
+
+        #[test]
+        #[should_panic(expected = "frozen")]
+        fn test_freeze_blocks_accumulate() {
+            let mut manager = $name::new();
+            manager.freeze();
+            manager.accumulate(0x1);
+        }
+
+        #[test]
+        #[should_panic(expected = "frozen")]
+        fn test_freeze_blocks_rollback() {
+            let mut manager = $name::new();
+            manager.accumulate(0x1);
+            manager.freeze();
+            manager.rollback(1);
+        }
+
+        #[test]
+        #[should_panic(expected = "frozen")]
+        fn test_freeze_blocks_load() {
+            let mut manager = $name::new();
+            manager.freeze();
+            manager.load(0x1);
+        }
+
+        #[test]
+        #[should_panic(expected = "frozen")]
+        fn test_freeze_blocks_rollback_to_last_commit() {
+            let mut manager = $name::new();
+            manager.accumulate(0x1);
+            manager.freeze();
+            manager.rollback_to_last_commit();
+        }
+
+        #[test]
+        #[should_panic(expected = "frozen")]
+        fn test_freeze_blocks_mark_commit() {
+            let mut manager = $name::new();
+            manager.accumulate(0x1);
+            manager.freeze();
+            manager.mark_commit();
+        }
+
+        #[test]
+        fn test_accumulate_bit_range() {
+            let mut manager = $name::new();
+            manager.load(0);
+            manager.accumulate_bit_range(4, 12).unwrap();
+            assert_eq!(manager.reconstruct(), 0x0FF0);
+            assert_eq!(manager.history_size(), 1);
+
+            // Flipping an overlapping range toggles the shared bits back
+            manager.accumulate_bit_range(8, 16).unwrap();
+            assert_eq!(manager.reconstruct(), 0xF0F0);
+        }
+
+        #[test]
+        fn test_accumulate_bit_range_validation() {
+            let mut manager = $name::new();
+            let width = $state::BITS;
+            assert_eq!(
+                manager.accumulate_bit_range(8, 8),
+                Err(DeltaError::InvalidBitRange { lo: 8, hi: 8, width })
+            );
+            assert!(manager.accumulate_bit_range(9, 3).is_err());
+            assert!(manager.accumulate_bit_range(0, width + 1).is_err());
+            assert_eq!(manager.history_size(), 0);
+
+            // The full width is a valid range
+            manager.accumulate_bit_range(0, width).unwrap();
+            assert_eq!(manager.reconstruct(), $state::MAX);
+        }
+
+        #[test]
+        fn test_accumulate_streaming() {
+            let mut manager = $name::new();
+            manager.load(0x100);
+            let mut emitted = Vec::new();
+            manager.accumulate_streaming([0x1, 0x2, 0x1].into_iter(), |state| emitted.push(state));
+
+            assert_eq!(emitted, vec![0x101, 0x103, 0x102]);
+            let per_step: Vec<_> = manager.iter_states().map(|(_, state)| state).collect();
+            assert_eq!(emitted, per_step);
+        }
+
+        #[cfg(feature = "bincode")]
+        #[test]
+        fn test_bincode_round_trip() {
+            let mut manager = $name::new();
+            manager.load(0xAAAA);
+            manager.accumulate_tagged(0x0F0F, 7);
+            manager.accumulate(0x00FF);
+
+            let bytes = manager.to_bincode().unwrap();
+            assert_eq!(bytes[0], $name::BINCODE_VERSION);
+            let mut restored = $name::from_bincode(&bytes).unwrap();
+            assert_eq!(restored.reconstruct(), manager.reconstruct());
+            assert_eq!(restored.history_size(), 2);
+            assert_eq!(restored.rollback_to_tag(7), 2);
+            assert_eq!(restored.reconstruct(), 0xAAAA);
+        }
+
+        #[cfg(feature = "bincode")]
+        #[test]
+        fn test_bincode_rejects_unknown_version() {
+            use atomik_video_streaming::BincodeError;
+
+            let mut bytes = $name::new().to_bincode().unwrap();
+            bytes[0] = $name::BINCODE_VERSION + 1;
+            assert!(matches!(
+                $name::from_bincode(&bytes),
+                Err(BincodeError::UnsupportedVersion(v)) if v == $name::BINCODE_VERSION + 1
+            ));
+            assert!(matches!($name::from_bincode(&[]), Err(BincodeError::MissingVersion)));
+        }
+
+        #[cfg(feature = "bincode")]
+        #[test]
+        fn test_bincode_rejects_inconsistent_payload() {
+            use atomik_video_streaming::BincodeError;
+
+            // Two payloads that differ only in the commit marker position
+            let encode_with_commit_after = |marked: usize| {
+                let mut manager = $name::from_state(0);
+                for delta in 1..=3 {
+                    manager.accumulate(delta);
+                    if delta == marked as $state {
+                        manager.mark_commit();
+                    }
+                }
+                manager.to_bincode().unwrap()
+            };
+            let valid = encode_with_commit_after(2);
+            let other = encode_with_commit_after(1);
+            let offset = (0..valid.len()).find(|&i| valid[i] != other[i]).unwrap();
+
+            let mut past_history = valid.clone();
+            past_history[offset] = 9;
+            assert!(matches!($name::from_bincode(&past_history), Err(BincodeError::Invalid(_))));
+
+            let mut trailing = valid.clone();
+            trailing.push(0);
+            assert!(matches!($name::from_bincode(&trailing), Err(BincodeError::Invalid(_))));
+
+            let mut restored = $name::from_bincode(&valid).unwrap();
+            assert_eq!(restored.rollback_to_last_commit(), 1);
+        }
+
+        #[test]
+        fn test_peak_history_size() {
+            let mut manager = $name::new();
+            manager.load(0);
+            for delta in 1..=5 {
+                manager.accumulate(delta);
+            }
+            manager.rollback(3);
+            manager.accumulate(0x10);
+            assert_eq!(manager.history_size(), 3);
+            assert_eq!(manager.peak_history_size(), 5);
+
+            manager.load(0);
+            assert_eq!(manager.peak_history_size(), 0);
+        }
+
+        #[test]
+        fn test_remove_delta() {
+            let mut manager = $name::new();
+            manager.load(0x1000);
+            manager.accumulate(0x1);
+            manager.accumulate(0x2);
+            manager.accumulate(0x4);
+
+            assert!(manager.remove_delta(0x2));
+            assert_eq!(manager.reconstruct(), 0x1000 ^ 0x1 ^ 0x4);
+            assert_eq!(manager.history_size(), 2);
+            // Remaining history rolls back cleanly to the initial state
+            assert_eq!(manager.rollback_collect(2), vec![0x4, 0x1]);
+            assert_eq!(manager.reconstruct(), 0x1000);
+
+            assert!(!manager.remove_delta(0x8));
+        }
+
+        #[test]
+        fn test_no_history_mode() {
+            let mut manager = $name::new_no_history();
+            manager.load(0xAAAA);
+            manager.accumulate(0x5555);
+            manager.accumulate_tagged(0x0001, 1);
+            assert_eq!(manager.reconstruct(), 0xFFFE);
+            assert_eq!(manager.history_size(), 0);
+            assert_eq!(manager.history_capacity(), 0);
+
+            assert_eq!(manager.rollback(1), 0);
+            assert_eq!(manager.reconstruct(), 0xFFFE);
+        }
+
+        #[test]
+        fn test_load_full() {
+            let mut manager = $name::new();
+            manager.accumulate(0x1234);
+            manager.load_full(0xAAAA0000, 0x0000BBBB);
+            assert_eq!(manager.get_initial_state(), 0xAAAA0000);
+            assert_eq!(manager.get_accumulator(), 0x0000BBBB);
+            assert_eq!(manager.reconstruct(), 0xAAAABBBB);
+            assert_eq!(manager.history_size(), 0);
+            #[cfg(not(feature = "strict"))]
+            assert_eq!(manager.rollback(1), 0);
+        }
+
+        #[test]
+        fn test_rolling_delta_stats() {
+            let mut manager = $name::new();
+            assert!(manager.delta_stats().is_none());
+            manager.enable_delta_stats(3);
+
+            let expected = [
+                (5, 5, 5.0),  // [5]
+                (1, 5, 3.0),  // [5, 1]
+                (1, 9, 5.0),  // [5, 1, 9]
+                (1, 9, 4.0),  // [1, 9, 2]
+                (2, 9, 6.0),  // [9, 2, 7]
+                (2, 7, 4.0),  // [2, 7, 3]
+            ];
+            for (delta, (min, max, mean)) in [5, 1, 9, 2, 7, 3].into_iter().zip(expected) {
+                manager.accumulate(delta);
+                let stats = manager.delta_stats().unwrap();
+                assert_eq!(stats.min(), Some(min));
+                assert_eq!(stats.max(), Some(max));
+                assert_eq!(stats.mean(), Some(mean));
+            }
+
+            manager.load(0);
+            assert!(manager.delta_stats().unwrap().is_empty());
+            assert_eq!(manager.delta_stats().unwrap().mean(), None);
+        }
+
+        #[test]
+        fn test_rollback_depth_histogram() {
+            let mut manager = $name::new();
+            for delta in 1..=64 {
+                manager.accumulate(delta);
+            }
+            for count in [1, 1, 2, 3, 4, 5, 16, 0] {
+                manager.rollback(count);
+            }
+
+            let histogram = manager.rollback_depth_histogram();
+            let expected: Vec<(usize, u64)> = vec![(0, 1), (1, 2), (2, 1), (4, 2), (8, 1), (16, 1)];
+            assert_eq!(histogram.into_iter().collect::<Vec<_>>(), expected);
+        }
+
+        #[test]
+        fn test_rollback_to_last_commit() {
+            let mut manager = $name::new();
+            manager.load(0);
+            manager.accumulate(0x1);
+            manager.mark_commit();
+            manager.accumulate(0x2);
+            manager.accumulate(0x4);
+            manager.mark_commit();
+            manager.accumulate(0x8);
+            manager.accumulate(0x10);
+
+            assert_eq!(manager.rollback_to_last_commit(), 2);
+            assert_eq!(manager.reconstruct(), 0x7);
+            // Already at the commit point
+            assert_eq!(manager.rollback_to_last_commit(), 0);
+
+            // Rolling back past a commit discards its marker
+            manager.rollback(1);
+            assert_eq!(manager.rollback_to_last_commit(), 1);
+            assert_eq!(manager.reconstruct(), 0x1);
+        }
+
+        #[test]
+        fn test_commit_markers_follow_eviction() {
+            let mut manager = $name::new();
+            manager.accumulate(0x1);
+            manager.mark_commit();
+            let capacity = manager.max_history();
+            for _ in 0..capacity {
+                manager.accumulate(0x2);
+            }
+            // Only 0x1 was evicted: the marker now sits at the window start
+            assert_eq!(manager.history_size(), capacity);
+            manager.accumulate(0x2);
+            // One more eviction pushes the marker out of the window
+            #[cfg(not(feature = "strict"))]
+            assert_eq!(manager.rollback_to_last_commit(), 0);
+            assert_eq!(manager.history_size(), capacity);
+        }
+
+        #[test]
+        fn test_reconcile() {
+            let mut left = $name::from_state(0x1000);
+            let mut right = $name::from_state(0x1000);
+            left.accumulate(0x0011);
+            right.accumulate(0x0101);
+
+            let conflict = left.reconcile(right.reconstruct());
+            assert_eq!(conflict, 0x0110);
+            assert_eq!(left.reconstruct(), right.reconstruct());
+            assert_eq!(left.history_size(), 2);
+
+            left.rollback(1);
+            assert_eq!(left.reconstruct(), 0x1011);
+        }
+
+        #[test]
+        fn test_dump_history() {
+            let mut manager = $name::new();
+            manager.load(0xFF);
+            manager.accumulate(0xABCD);
+            manager.accumulate(0x1);
+            manager.accumulate(0x2);
+
+            let dump = manager.dump_history();
+            let lines: Vec<&str> = dump.lines().collect();
+            assert_eq!(lines.len(), 4);
+            assert!(lines[0].starts_with(concat!(stringify!($name), " initial=0x")));
+            assert!(lines[0].ends_with("deltas=3"));
+            assert_eq!(lines[1], format!("0x{:0width$X}", 0xABCD, width = ($state::BITS / 4) as usize));
+        }
+
+        #[test]
+        fn test_states_equal_unordered() {
+            let deltas = [0x1111, 0x2020, 0x0303, 0x4444, 0x0505];
+            let shuffled = [0x0303, 0x0505, 0x1111, 0x4444, 0x2020];
+            assert!($name::states_equal_unordered(&deltas, &shuffled, 0xABCD));
+
+            // Managers applying either ordering agree
+            let mut left = $name::from_state(0xABCD);
+            let mut right = $name::from_state(0xABCD);
+            deltas.iter().for_each(|&d| left.accumulate(d));
+            shuffled.iter().for_each(|&d| right.accumulate(d));
+            assert_eq!(left.reconstruct(), right.reconstruct());
+
+            assert!(!$name::states_equal_unordered(&deltas, &shuffled[1..], 0xABCD));
+        }
+
+        #[test]
+        fn test_max_history_bytes() {
+            let mut manager = $name::new();
+            manager.set_max_history_bytes(Some(10 * $name::HISTORY_ENTRY_BYTES));
+            for delta in 1..=20 {
+                manager.accumulate(delta);
+            }
+            // The byte budget evicts long before the count cap
+            assert_eq!(manager.history_size(), 10);
+            assert!(manager.history_size() < manager.max_history());
+            assert!(manager.history_bytes() <= manager.max_history_bytes().unwrap());
+            assert_eq!(manager.reconstruct(), (1..=20).fold(0, |acc, d| acc ^ d));
+
+            // Tightening the budget trims the retained history immediately
+            manager.set_max_history_bytes(Some(4 * $name::HISTORY_ENTRY_BYTES - 1));
+            assert_eq!(manager.history_size(), 3);
+            assert_eq!(manager.rollback_collect(3), vec![20, 19, 18]);
+        }
+
+        #[test]
+        fn test_reconstruct_without() {
+            let mut manager = $name::from_state(0xF000);
+            let mut without = $name::from_state(0xF000);
+            for delta in [0x0001, 0x0020, 0x0300, 0x0044] {
+                manager.accumulate(delta);
+                if delta != 0x0020 {
+                    without.accumulate(delta);
+                }
+            }
+            assert_eq!(manager.reconstruct_without(0x0020), without.reconstruct());
+            // Read-only
+            assert_eq!(manager.history_size(), 4);
+        }
+
+        #[test]
+        fn test_load_shrinking() {
+            let mut manager = $name::new();
+            for delta in 0..1000 {
+                manager.accumulate(delta);
+            }
+            let peak = manager.history_capacity();
+            assert!(peak >= 1000);
+
+            // Plain load keeps the allocation around
+            manager.load(0);
+            assert_eq!(manager.history_capacity(), peak);
+
+            for delta in 0..1000 {
+                manager.accumulate(delta);
+            }
+            manager.load_shrinking(0x42);
+            assert!(manager.history_capacity() < peak);
+            assert_eq!(manager.reconstruct(), 0x42);
+            assert_eq!(manager.history_size(), 0);
+        }
+
+        #[test]
+        fn test_set_and_clear_flags() {
+            let mut manager = $name::from_state(0b1000_0001);
+            manager.set_flags(0b0000_0110);
+            assert_eq!(manager.reconstruct(), 0b1000_0111);
+            // Setting again is a no-op on state
+            manager.set_flags(0b0000_0110);
+            assert_eq!(manager.reconstruct(), 0b1000_0111);
+
+            manager.clear_flags(0b1000_0100);
+            assert_eq!(manager.reconstruct(), 0b0000_0011);
+            manager.clear_flags(0b1000_0100);
+            assert_eq!(manager.reconstruct(), 0b0000_0011);
+
+            // Each call is still a rollback-able delta
+            manager.rollback(2);
+            assert_eq!(manager.reconstruct(), 0b1000_0111);
+        }
+
+        #[test]
+        fn test_try_accumulate_batch() {
+            let mut manager = $name::from_state(0x100);
+            manager.try_accumulate_batch(&[0x1, 0x2, 0x4]).unwrap();
+            assert_eq!(manager.reconstruct(), 0x107);
+            assert_eq!(manager.rollback(3), 3);
+            assert_eq!(manager.reconstruct(), 0x100);
+        }
+
+        #[test]
+        fn test_try_accumulate_batch_rejects_whole_batch() {
+            let mut manager = $name::from_state(0x100);
+            manager.accumulate(0x10);
+            manager.set_max_history_bytes(Some(3 * $name::HISTORY_ENTRY_BYTES));
+
+            // The fourth delta would evict the first one of the same batch
+            assert_eq!(
+                manager.try_accumulate_batch(&[0x1, 0x2, 0x4, 0x8, 0x20]),
+                Err(BatchError::HistoryOverflow { index: 3, capacity: 3 })
+            );
+            assert_eq!(manager.reconstruct(), 0x110);
+            assert_eq!(manager.history_size(), 1);
+
+            manager.freeze();
+            assert_eq!(manager.try_accumulate_batch(&[0x1]), Err(BatchError::Frozen));
+            assert_eq!(manager.reconstruct(), 0x110);
+        }
+
+        #[test]
+        fn test_try_accumulate_batch_while_paused() {
+            let mut manager = $name::from_state(0x100);
+            manager.set_max_history_bytes(Some(2 * $name::HISTORY_ENTRY_BYTES));
+            manager.pause_history();
+
+            // Nothing is recorded, so the history cap does not apply
+            assert_eq!(manager.try_accumulate_batch(&[0x1, 0x2, 0x4]), Ok(()));
+            assert_eq!(manager.reconstruct(), 0x107);
+            assert_eq!(manager.history_size(), 0);
+        }
+
+        #[test]
+        fn test_magnitude_sum() {
+            let mut manager = $name::new();
+            manager.accumulate(100);
+            manager.accumulate(100);
+            manager.accumulate(55);
+            // XOR cancelled the pair, the magnitude sum did not
+            assert_eq!(manager.reconstruct(), 55);
+            assert_eq!(manager.magnitude_sum(), 255);
+
+            manager.accumulate($state::MAX);
+            manager.accumulate($state::MAX);
+            assert_eq!(manager.magnitude_sum(), 255u128.saturating_add(u128::from($state::MAX).saturating_mul(2)));
+
+            manager.load(0);
+            assert_eq!(manager.magnitude_sum(), 0);
+        }
+
+        #[test]
+        fn test_first_divergence() {
+            let mut left = $name::new();
+            let mut right = $name::new();
+            for delta in [0x1, 0x2, 0x3] {
+                left.accumulate(delta);
+                right.accumulate(delta);
+            }
+            assert_eq!(left.first_divergence(&right), None);
+
+            left.accumulate(0x4);
+            // Right is a strict prefix of left
+            assert_eq!(left.first_divergence(&right), Some(3));
+            assert_eq!(right.first_divergence(&left), Some(3));
+
+            right.accumulate(0x5);
+            right.accumulate(0x4);
+            assert_eq!(left.first_divergence(&right), Some(3));
+            assert_eq!(left.iter_history().copied().collect::<Vec<_>>(), vec![0x1, 0x2, 0x3, 0x4]);
+        }
+
+        #[cfg(not(feature = "strict"))]
+        #[test]
+        fn test_lenient_rollback_caps() {
+            let mut manager = $name::new();
+            manager.accumulate(0x1);
+            assert_eq!(manager.rollback(5), 1);
+            assert_eq!(manager.rollback_to_tag(42), 0);
+            assert_eq!(manager.rollback_to_last_commit(), 0);
+        }
+
+        #[cfg(feature = "strict")]
+        #[test]
+        #[should_panic(expected = "exceeds retained history")]
+        fn test_strict_rollback_beyond_history() {
+            let mut manager = $name::new();
+            manager.accumulate(0x1);
+            manager.rollback(5);
+        }
+
+        #[cfg(feature = "strict")]
+        #[test]
+        #[should_panic(expected = "no retained delta tagged 42")]
+        fn test_strict_rollback_to_unknown_tag() {
+            let mut manager = $name::new();
+            manager.accumulate_tagged(0x1, 7);
+            manager.rollback_to_tag(42);
+        }
+
+        #[cfg(feature = "strict")]
+        #[test]
+        #[should_panic(expected = "no commit marker")]
+        fn test_strict_rollback_without_commit() {
+            let mut manager = $name::new();
+            manager.accumulate(0x1);
+            manager.rollback_to_last_commit();
+        }
+
+        #[test]
+        fn test_accumulate_nonzero() {
+            let mut manager = $name::from_state(0x10);
+            manager.enable_delta_stats(4);
+
+            assert!(!manager.accumulate_nonzero(0));
+            assert_eq!(manager.history_size(), 0);
+            assert_eq!(manager.peak_history_size(), 0);
+            assert!(manager.delta_stats().unwrap().is_empty());
+
+            assert!(manager.accumulate_nonzero(0x3));
+            assert_eq!(manager.history_size(), 1);
+            assert_eq!(manager.magnitude_sum(), 3);
+            assert_eq!(manager.reconstruct(), 0x13);
+        }
+
+        #[test]
+        fn test_fingerprint_matching() {
+            let mut left = $name::from_state(0x1234);
+            let mut right = $name::new();
+            left.accumulate(0x00FF);
+            right.accumulate(0x1234 ^ 0x00FF);
+            assert!(left.matches_fingerprint(&right.fingerprint_bytes()));
+            assert_eq!(left.fingerprint(), right.fingerprint());
+
+            right.accumulate(0x1);
+            assert!(!left.matches_fingerprint(&right.fingerprint_bytes()));
+        }
+
+        #[cfg(feature = "mmap")]
+        #[test]
+        fn test_replay_from_mmap() {
+            use std::io::ErrorKind;
+
+            let dir = std::env::temp_dir();
+            let path = dir.join(format!(concat!(stringify!($name), "_replay_{}.log"), std::process::id()));
+            let deltas: [$state; 3] = [0x0F, 0xF0, 0x0300];
+            let mut log: Vec<u8> = deltas.iter().flat_map(|d| d.to_le_bytes()).collect();
+            std::fs::write(&path, &log).unwrap();
+
+            let mut manager = $name::replay_from_mmap(0x1000, &path).unwrap();
+            assert_eq!(manager.reconstruct(), 0x1000 ^ 0x0F ^ 0xF0 ^ 0x0300);
+            assert_eq!(manager.history_size(), 3);
+            manager.rollback(1);
+            assert_eq!(manager.reconstruct(), 0x1000 ^ 0x0F ^ 0xF0);
+
+            log.push(0xAB);
+            std::fs::write(&path, &log).unwrap();
+            let err = $name::replay_from_mmap(0x1000, &path).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+            std::fs::remove_file(&path).unwrap();
+        }
+
+        #[test]
+        fn test_hamming_distance() {
+            let mut manager = $name::from_state(0xF0F0);
+            assert_eq!(manager.hamming_distance(0xF0F0), 0);
+
+            manager.accumulate(0x0101);
+            assert_eq!(manager.hamming_distance(0xF0F0), 2);
+            assert_eq!(manager.hamming_distance(!manager.reconstruct()), $state::BITS);
+        }
+
+        #[test]
+        fn test_iter_delta_effects() {
+            let mut manager = $name::new();
+            manager.load(0);
+            manager.accumulate(0x1);
+            manager.accumulate(0xFF);
+            manager.accumulate(0);
+
+            let effects: Vec<_> = manager.iter_delta_effects().collect();
+            assert_eq!(effects, vec![(0x1, 1), (0xFF, 8), (0, 0)]);
+        }
+
+        #[test]
+        fn test_fast_rollback_matches_collect() {
+            let mut fast = $name::new();
+            fast.load(0x5555);
+            for delta in 1..=2000 {
+                fast.accumulate(delta * 0x9E37);
+            }
+            let mut slow = fast.clone();
+            let count = 1000.min(fast.history_size());
+
+            assert_eq!(fast.rollback(count), count);
+            assert_eq!(slow.rollback_collect(count).len(), count);
+            assert_eq!(fast.get_accumulator(), slow.get_accumulator());
+            assert_eq!(fast.reconstruct(), slow.reconstruct());
+            assert!(fast.iter_history().eq(slow.iter_history()));
+        }
+
+        #[test]
+        fn test_compact_canceling() {
+            let mut manager = $name::new();
+            manager.load(0x1000);
+            manager.accumulate(0x1);
+            manager.accumulate(0x0F0F);
+            manager.accumulate(0x2);
+            manager.accumulate(0x0F0F);
+            manager.accumulate(0x4);
+            let before = manager.reconstruct();
+
+            assert_eq!(manager.compact_canceling(), 2);
+            assert_eq!(manager.reconstruct(), before);
+            assert_eq!(manager.iter_history().copied().collect::<Vec<_>>(), vec![0x1, 0x2, 0x4]);
+            assert_eq!(manager.compact_canceling(), 0);
+
+            manager.rollback(1);
+            assert_eq!(manager.reconstruct(), 0x1000 ^ 0x1 ^ 0x2);
+        }
+
+        #[test]
+        fn test_builder_track_history() {
+            let mut tracked = $name::builder().initial_state(0x10).build();
+            let mut untracked = $name::builder().initial_state(0x10).track_history(false).build();
+            for manager in [&mut tracked, &mut untracked] {
+                manager.accumulate(0x1);
+                manager.accumulate(0x2);
+            }
+
+            assert_eq!(tracked.history_size(), 2);
+            assert_eq!(tracked.rollback(1), 1);
+            assert_eq!(tracked.reconstruct(), 0x10 ^ 0x1);
+
+            assert_eq!(untracked.history_size(), 0);
+            assert_eq!(untracked.history_capacity(), 0);
+            assert_eq!(untracked.rollback(1), 0);
+            assert_eq!(untracked.reconstruct(), 0x10 ^ 0x1 ^ 0x2);
+        }
+
+        #[test]
+        fn test_swap_accumulator() {
+            let mut manager = $name::from_state(0x1000);
+            manager.accumulate(0x0F);
+            manager.accumulate(0xF0);
+
+            assert_eq!(manager.swap_accumulator(0x3), 0xFF);
+            assert_eq!(manager.get_accumulator(), 0x3);
+            assert_eq!(manager.reconstruct(), 0x1000 ^ 0x3);
+            assert_eq!(manager.history_size(), 0);
+        }
+
+        #[test]
+        fn test_accumulate_checked() {
+            let mut manager = $name::from_state(0x8000);
+            assert_eq!(manager.accumulate_checked(0x00F0, 0x00FF), Ok(()));
+            assert_eq!(manager.reconstruct(), 0x80F0);
+
+            assert_eq!(
+                manager.accumulate_checked(0x0101, 0x00FF),
+                Err(DeltaError::MaskViolation { reserved_bits: 0x0100 })
+            );
+            assert_eq!(manager.reconstruct(), 0x80F0);
+            assert_eq!(manager.history_size(), 1);
+        }
+
+        #[test]
+        fn test_hex_state_round_trip() {
+            let mut manager = $name::from_state(0xAB00);
+            manager.accumulate(0x00CD);
+            let hex = manager.to_hex_state();
+            assert!(hex.starts_with("0x") && hex.ends_with("ABCD"));
+            assert_eq!(hex.len(), 2 + ($state::BITS / 4) as usize);
+
+            assert_eq!($name::from_hex_state(&hex).unwrap().reconstruct(), 0xABCD);
+            assert_eq!($name::from_hex_state("abcd").unwrap().reconstruct(), 0xABCD);
+        }
+
+        #[test]
+        fn test_hex_state_rejects_invalid_input() {
+            assert!($name::from_hex_state("0xXYZ").is_err());
+            assert!($name::from_hex_state("0x").is_err());
+            assert!($name::from_hex_state("").is_err());
+            assert!($name::from_hex_state("0x+ff").is_err());
+            assert!($name::from_hex_state("+ff").is_err());
+            assert!($name::from_hex_state("-ff").is_err());
+            assert_eq!($name::from_hex_state("0XFF").unwrap().reconstruct(), 0xFF);
+        }
+
+        #[test]
+        fn test_reconstruct_as() {
+            use atomik_video_streaming::FromBits;
+
+            #[derive(Debug, PartialEq)]
+            struct PriceFields {
+                bid: u16,
+                ask: u16,
+                last: u16,
+                volume: u16,
+            }
+
+            impl FromBits<$state> for PriceFields {
+                fn from_bits(bits: $state) -> Self {
+                    PriceFields {
+                        bid: bits as u16,
+                        ask: (bits >> 16) as u16,
+                        last: (bits >> 32) as u16,
+                        volume: (bits >> 48) as u16,
+                    }
+                }
+            }
+
+            let mut manager = $name::from_state(0x0004_0003_0002_0001);
+            assert_eq!(
+                manager.reconstruct_as::<PriceFields>(),
+                PriceFields { bid: 1, ask: 2, last: 3, volume: 4 }
+            );
+
+            manager.accumulate(0x0000_0000_00FF_0000);
+            let fields: PriceFields = manager.reconstruct_as();
+            assert_eq!(fields.ask, 0x00FD);
+        }
+
+        #[test]
+        fn test_replay_with_progress() {
+            let deltas: Vec<$state> = (1..=10_000).map(|i| i * 0x9E37).collect();
+            let mut reports = Vec::new();
+            let replayed = $name::replay_with_progress(0x42, &deltas, 1_000, |applied| reports.push(applied));
+
+            assert_eq!(reports, (1..=10).map(|i| i * 1_000).collect::<Vec<_>>());
+            let mut plain = $name::from_state(0x42);
+            for &delta in &deltas {
+                plain.accumulate(delta);
+            }
+            assert_eq!(replayed.reconstruct(), plain.reconstruct());
+        }
+
+        #[test]
+        fn test_history_pressure_alarm() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+
+            static CROSSINGS: AtomicUsize = AtomicUsize::new(0);
+
+            let mut manager = $name::new();
+            manager.load(0);
+            assert_eq!(manager.history_pressure(), 0.0);
+            manager.set_pressure_alarm(0.9, |_| {
+                CROSSINGS.fetch_add(1, Ordering::SeqCst);
+            });
+
+            let threshold = manager.max_history() * 9 / 10;
+            for delta in 0..threshold as $state + 10 {
+                manager.accumulate(delta);
+            }
+            assert!(manager.history_pressure() >= 0.9);
+            assert_eq!(CROSSINGS.load(Ordering::SeqCst), 1);
+
+            manager.rollback(20);
+            assert!(manager.history_pressure() < 0.9);
+            for delta in 0..20 {
+                manager.accumulate(delta);
+            }
+            assert_eq!(CROSSINGS.load(Ordering::SeqCst), 2);
+        }
+
+        #[test]
+        fn test_history_pressure_respects_byte_cap() {
+            let mut manager = $name::new();
+            manager.load(0);
+            manager.set_max_history_bytes(Some(10 * $name::HISTORY_ENTRY_BYTES));
+            for delta in 0..5 {
+                manager.accumulate(delta);
+            }
+            assert_eq!(manager.history_pressure(), 0.5);
+            for delta in 0..20 {
+                manager.accumulate(delta);
+            }
+            assert_eq!(manager.history_size(), 10);
+            assert_eq!(manager.history_pressure(), 1.0);
+        }
+
+        #[cfg(feature = "latency")]
+        #[test]
+        fn test_accumulate_latency_percentiles() {
+            use std::time::Duration;
+
+            let mut manager = $name::new();
+            assert_eq!(manager.accumulate_latency_percentile(50.0), Duration::ZERO);
+            manager.load(0);
+            for delta in 0..10_000 {
+                manager.accumulate(delta);
+            }
+
+            let p50 = manager.accumulate_latency_percentile(50.0);
+            let p99 = manager.accumulate_latency_percentile(99.0);
+            let max = manager.accumulate_latency_percentile(100.0);
+            assert!(p50 <= p99 && p99 <= max);
+        }
+
+        #[test]
+        fn test_split_at() {
+            let mut manager = $name::new();
+            manager.load(0x1000);
+            for delta in [0x1, 0x2, 0x4, 0x8, 0x10] {
+                manager.accumulate(delta);
+            }
+            let original = manager.clone();
+
+            let suffix = manager.split_at(2);
+            assert_eq!(manager.history_size(), 2);
+            assert_eq!(manager.reconstruct(), 0x1000 ^ 0x1 ^ 0x2);
+            assert_eq!(suffix.get_initial_state(), manager.reconstruct());
+            assert_eq!(suffix.reconstruct(), original.reconstruct());
+
+            let rejoined: Vec<_> = manager.iter_history().chain(suffix.iter_history()).collect();
+            assert!(rejoined.into_iter().eq(original.iter_history()));
+        }
+
+        #[test]
+        fn test_split_at_tail_is_loaded() {
+            let mut manager = $name::builder().strict_load(true).initial_state(0x10).build();
+            manager.accumulate(0x1);
+            manager.accumulate(0x2);
+
+            let mut tail = manager.split_at(1);
+            assert!(tail.is_loaded());
+            assert_eq!(tail.try_accumulate(0x4), Ok(()));
+            assert_eq!(tail.reconstruct(), 0x10 ^ 0x1 ^ 0x2 ^ 0x4);
+        }
+
+        #[test]
+        fn test_split_at_keeps_settings() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+            static CROSSINGS: AtomicUsize = AtomicUsize::new(0);
+
+            let mut manager = $name::builder().growth_chunk(100).build();
+            manager.enable_delta_stats(8);
+            manager.set_pressure_alarm(0.5, |_| {
+                CROSSINGS.fetch_add(1, Ordering::SeqCst);
+            });
+            for delta in 1..=3 {
+                manager.accumulate(delta);
+            }
+
+            let mut tail = manager.split_at(1);
+            assert_eq!(tail.history_capacity(), 100);
+            assert_eq!(tail.delta_stats().map(|stats| stats.window()), Some(8));
+            assert_eq!(tail.delta_stats().map(|stats| stats.len()), Some(0));
+
+            for delta in 0..tail.max_history() as $state {
+                tail.accumulate(delta);
+            }
+            assert_eq!(CROSSINGS.load(Ordering::SeqCst), 1);
+        }
+
+        #[test]
+        fn test_previous_initials() {
+            let mut manager = $name::new();
+            manager.load(0x10);
+            manager.accumulate(0x1);
+            manager.load(0x20);
+            manager.load(0x30);
+            assert_eq!(manager.previous_initials().copied().collect::<Vec<_>>(), vec![0x10, 0x20]);
+            assert_eq!(manager.get_initial_state(), 0x30);
+
+            for state in 0..$name::INITIAL_HISTORY_DEPTH as $state {
+                manager.load(state);
+            }
+            assert_eq!(manager.previous_initials().count(), $name::INITIAL_HISTORY_DEPTH);
+            assert_eq!(manager.previous_initials().next(), Some(&0x30));
+        }
+
+        #[test]
+        fn test_reconstruct_byte_order() {
+            let mut manager = $name::from_state(0x0102_0304_0000_0000);
+            manager.accumulate(0x0506_0708);
+
+            let be = manager.reconstruct_be_bytes();
+            let le = manager.reconstruct_le_bytes();
+            assert_eq!(be[be.len() - 8..], [1, 2, 3, 4, 5, 6, 7, 8]);
+            assert_eq!(le[..8], [8, 7, 6, 5, 4, 3, 2, 1]);
+            assert!(be[..be.len() - 8].iter().chain(&le[8..]).all(|&byte| byte == 0));
+        }
+
+        #[test]
+        fn test_estimate_rollback_ops() {
+            let mut manager = $name::new();
+            manager.load(0);
+            for delta in 1..=4 {
+                manager.accumulate(delta);
+            }
+            assert_eq!(manager.estimate_rollback_ops(2), 2);
+            assert_eq!(manager.estimate_rollback_ops(4), 4);
+            assert_eq!(manager.estimate_rollback_ops(10), 4);
+            assert_eq!(manager.history_size(), 4);
+        }
+
+        #[test]
+        fn test_export_history_to_matches_export_history() {
+            let mut manager = $name::new();
+            manager.load(0x1234);
+            for delta in 1..=600 {
+                manager.accumulate(delta);
+            }
+
+            let mut sink = Vec::new();
+            manager.export_history_to(&mut sink).unwrap();
+            assert_eq!(sink, manager.export_history());
+
+            let width = std::mem::size_of::<$state>();
+            assert_eq!(sink.len(), 2 * width + 8 + manager.history_size() * width);
+            assert_eq!(sink[..width], (0x1234 as $state).to_le_bytes());
+            assert_eq!(sink[2 * width..2 * width + 8], (manager.history_size() as u64).to_le_bytes());
+        }
+
+        #[test]
+        fn test_preview() {
+            let mut manager = $name::from_state(0xF000);
+            manager.accumulate(0x0F00);
+
+            let previewed = manager.preview(0x00F0);
+            assert_eq!(manager.reconstruct(), 0xFF00);
+            assert_eq!(manager.history_size(), 1);
+
+            manager.accumulate(0x00F0);
+            assert_eq!(manager.reconstruct(), previewed);
+        }
+
+        #[test]
+        fn test_accumulate_event() {
+            let mut manager = $name::from_state(0x100);
+            manager.accumulate(0x1);
+
+            let first = manager.accumulate_event(0x2);
+            let second = manager.accumulate_event(0x4);
+            assert_eq!(first.seq, 1);
+            assert_eq!(second.seq, first.seq + 1);
+            assert_eq!(first.delta, 0x2);
+            assert_eq!(first.resulting_state, 0x103);
+            assert_eq!(second.resulting_state, manager.reconstruct());
+            assert!(second.timestamp >= first.timestamp);
+        }
+
+        #[test]
+        fn test_builder_growth_chunk() {
+            let mut manager = $name::builder().growth_chunk(100).build();
+            manager.accumulate(0x1);
+            assert_eq!(manager.history_capacity(), 100);
+            for delta in 2..=101 {
+                manager.accumulate(delta);
+            }
+            assert_eq!(manager.history_capacity(), 200);
+
+            for delta in 0..manager.max_history() as $state * 2 {
+                manager.accumulate(delta);
+            }
+            assert_eq!(manager.history_size(), manager.max_history());
+            assert!(manager.history_capacity() <= manager.max_history() + 1);
+        }
+
+        #[test]
+        fn test_rollback_to_state() {
+            let mut manager = $name::new();
+            manager.load(0x1000);
+            manager.accumulate(0x1);
+            let checkpoint = manager.reconstruct();
+            for delta in [0x2, 0x4, 0x8] {
+                manager.accumulate(delta);
+            }
+
+            assert_eq!(manager.rollback_to_state(manager.reconstruct(), 5), Some(0));
+            assert_eq!(manager.rollback_to_state(0x1000, 2), None);
+            assert_eq!(manager.reconstruct(), 0x100F);
+            assert_eq!(manager.history_size(), 4);
+
+            assert_eq!(manager.rollback_to_state(checkpoint, 5), Some(3));
+            assert_eq!(manager.reconstruct(), checkpoint);
+            assert_eq!(manager.history_size(), 1);
+        }
+
+        #[test]
+        fn test_accumulate_signed() {
+            let mut manager = $name::from_state(0xAAAA_0000_BBBB);
+            let field = |manager: &$name| (manager.reconstruct() >> 16) & 0xFFFF;
+
+            manager.accumulate_signed(16, 16, 5).unwrap();
+            assert_eq!(field(&manager), 5);
+            manager.accumulate_signed(16, 16, -3).unwrap();
+            assert_eq!(field(&manager), 2);
+            manager.accumulate_signed(16, 16, -3).unwrap();
+            assert_eq!(field(&manager), 0xFFFF);
+            assert_eq!(manager.reconstruct() & !0xFFFF_0000, 0xAAAA_0000_BBBB);
+
+            manager.rollback(1);
+            assert_eq!(field(&manager), 2);
+            assert!(matches!(
+                manager.accumulate_signed($state::BITS - 8, 16, 1),
+                Err(DeltaError::InvalidBitRange { .. })
+            ));
+            assert!(manager.accumulate_signed(0, 0, 1).is_err());
+        }
+
+        #[test]
+        fn test_pause_history() {
+            let mut manager = $name::new();
+            manager.load(0x1000);
+            manager.accumulate(0x1);
+
+            manager.pause_history();
+            assert!(manager.is_history_paused());
+            manager.accumulate(0x2);
+            manager.accumulate(0x4);
+            assert_eq!(manager.reconstruct(), 0x1007);
+            assert_eq!(manager.history_size(), 1);
+
+            manager.resume_history();
+            manager.accumulate(0x8);
+            assert_eq!(manager.history_size(), 2);
+            assert_eq!(manager.rollback(2), 2);
+            assert_eq!(manager.reconstruct(), 0x1006);
+        }
+
+        #[test]
+        fn test_accumulate_before_load_is_permissive_by_default() {
+            let mut manager = $name::new();
+            assert!(!manager.is_loaded());
+            assert_eq!(manager.try_accumulate(0x5), Ok(()));
+            manager.accumulate(0x2);
+            assert_eq!(manager.reconstruct(), 0x7);
+        }
+
+        #[test]
+        fn test_strict_load_rejects_accumulate_before_load() {
+            let mut manager = $name::builder().strict_load(true).build();
+            assert_eq!(manager.try_accumulate(0x5), Err(DeltaError::NotLoaded));
+            assert_eq!(manager.history_size(), 0);
+
+            manager.load(0x10);
+            assert!(manager.is_loaded());
+            assert_eq!(manager.try_accumulate(0x5), Ok(()));
+            assert_eq!(manager.reconstruct(), 0x15);
+
+            let seeded = $name::builder().strict_load(true).initial_state(0x10).build();
+            assert!(seeded.is_loaded());
+        }
+
+        #[test]
+        fn test_fallible_accumulates_reject_unloaded_and_frozen() {
+            let mut manager = $name::builder().strict_load(true).build();
+            assert_eq!(manager.accumulate_checked(0x1, 0xFF), Err(DeltaError::NotLoaded));
+            assert_eq!(manager.accumulate_bit_range(0, 4), Err(DeltaError::NotLoaded));
+            assert_eq!(manager.accumulate_signed(0, 8, 1), Err(DeltaError::NotLoaded));
+            assert_eq!(manager.try_accumulate_batch(&[0x1]), Err(BatchError::NotLoaded));
+            assert_eq!(manager.history_size(), 0);
+
+            manager.load(0);
+            manager.freeze();
+            assert_eq!(manager.try_accumulate(0x1), Err(DeltaError::Frozen));
+            assert_eq!(manager.accumulate_checked(0x1, 0xFF), Err(DeltaError::Frozen));
+            assert_eq!(manager.accumulate_bit_range(0, 4), Err(DeltaError::Frozen));
+            assert_eq!(manager.accumulate_signed(0, 8, 1), Err(DeltaError::Frozen));
+            assert_eq!(manager.try_accumulate_batch(&[0x1]), Err(BatchError::Frozen));
+            assert_eq!(manager.reconstruct(), 0);
+        }
+
+        #[test]
+        #[should_panic(expected = "not loaded")]
+        fn test_strict_load_accumulate_panics_before_load() {
+            let mut manager = $name::builder().strict_load(true).build();
+            manager.accumulate(0x1);
+        }
+
+        #[test]
+        fn test_from_origin_and_current() {
+            let mut manager = $name::from_origin_and_current(0x1111, 0x2468);
+            assert_eq!(manager.reconstruct(), 0x2468);
+            assert_eq!(manager.get_initial_state(), 0x1111);
+            assert_eq!(manager.history_size(), 1);
+
+            assert_eq!(manager.rollback(1), 1);
+            assert_eq!(manager.reconstruct(), 0x1111);
+        }
+
+        #[test]
+        fn test_check_sequence() {
+            use atomik_video_streaming::Op;
+
+            assert_eq!($name::check_sequence(&[]), Ok(()));
+            assert_eq!(
+                $name::check_sequence(&[
+                    Op::Load(0x1000),
+                    Op::Accumulate(0x1),
+                    Op::Accumulate(0x2),
+                    Op::Rollback(1),
+                    Op::Rollback(5),
+                    Op::Load(0x2000),
+                    Op::Accumulate(0x4),
+                ]),
+                Ok(())
+            );
+
+            // Overflow history so the oldest deltas are evicted, then unwind past them
+            let capacity = $name::new().max_history() as $state;
+            let mut ops = vec![Op::Load(0xABCD)];
+            ops.extend((1..=capacity + 10).map(|delta| Op::Accumulate(delta * 0x9E37)));
+            ops.push(Op::Rollback(capacity as usize + 5));
+            ops.push(Op::Accumulate(0xFF));
+            assert_eq!($name::check_sequence(&ops), Ok(()));
+        }
+    };
+}
@@ -0,0 +1,64 @@
+//! Integration tests for the delta_fields! macro
+
+use atomik_video_streaming::{delta_fields, PriceTick};
+
+delta_fields! {
+    /// Quote fields packed into a PriceTick state
+    pub struct Quote(PriceTick: u64) {
+        bid, set_bid: u16 = 16,
+        ask, set_ask: u16 = 16,
+        size, set_size: u32 = 32,
+    }
+}
+
+#[test]
+fn test_field_accessors() {
+    let mut quote = Quote::new(PriceTick::new());
+    quote.set_bid(0x1234);
+    quote.set_ask(0xABCD);
+    quote.set_size(0xDEADBEEF);
+    assert_eq!(quote.bid(), 0x1234);
+    assert_eq!(quote.ask(), 0xABCD);
+    assert_eq!(quote.size(), 0xDEADBEEF);
+    assert_eq!(quote.manager().reconstruct(), 0xDEADBEEFABCD1234);
+
+    // Rewriting one field leaves the others untouched
+    quote.set_ask(0x0001);
+    assert_eq!(quote.bid(), 0x1234);
+    assert_eq!(quote.ask(), 0x0001);
+    assert_eq!(quote.size(), 0xDEADBEEF);
+}
+
+#[test]
+fn test_field_setter_rollback() {
+    let mut manager = PriceTick::new();
+    manager.load(0xFFFF);
+    let mut quote = Quote::new(manager);
+    quote.set_ask(7);
+    quote.set_bid(0);
+    assert_eq!(quote.manager().history_size(), 2);
+
+    quote.manager_mut().rollback(1);
+    assert_eq!(quote.bid(), 0xFFFF);
+    assert_eq!(quote.ask(), 7);
+
+    let manager = quote.into_inner();
+    assert_eq!(manager.reconstruct(), 0x0007_FFFF);
+}
+
+#[test]
+fn test_for_each_field() {
+    let mut quote = Quote::new(PriceTick::new());
+    quote.set_bid(100);
+    quote.set_ask(250);
+    quote.set_size(70000);
+
+    let mut names = Vec::new();
+    let mut sum = 0;
+    quote.for_each_field(|name, value| {
+        names.push(name.to_string());
+        sum += value;
+    });
+    assert_eq!(names, ["bid", "ask", "size"]);
+    assert_eq!(sum, quote.bid() as u64 + quote.ask() as u64 + quote.size() as u64);
+}
@@ -0,0 +1,30 @@
+//! Integration tests for the DeltaManager trait
+
+use atomik_video_streaming::{DeltaManager, H264Delta, IMUFusion, PriceTick};
+
+fn replay_all<M: DeltaManager>(
+    manager: &mut M,
+    initial: M::State,
+    deltas: &[M::State],
+) -> M::State {
+    manager.load(initial);
+    for &delta in deltas {
+        manager.accumulate(delta);
+    }
+    manager.reconstruct()
+}
+
+#[test]
+fn test_generic_replay() {
+    let mut price = PriceTick::new();
+    let mut imu = IMUFusion::new();
+    let mut video = H264Delta::new();
+
+    assert_eq!(replay_all(&mut price, 0xF0, &[0x01, 0x02]), 0xF3);
+    assert_eq!(replay_all(&mut imu, 0xF0, &[0x01, 0x02]), 0xF3);
+    assert_eq!(replay_all(&mut video, u128::MAX, &[u128::MAX]), 0);
+
+    assert_eq!(DeltaManager::rollback(&mut price, 1), 1);
+    assert_eq!(DeltaManager::history_size(&imu), 2);
+    assert_eq!(price.reconstruct(), 0xF1);
+}
@@ -0,0 +1,27 @@
+//! Integration tests for DeltaSketch
+
+use atomik_video_streaming::{DeltaSketch, PriceTick};
+
+#[test]
+fn test_diff_recovers_small_difference() {
+    let shared: Vec<u64> = (1..=200).map(|i| i * 0x9E37_79B9).collect();
+    let mut left = PriceTick::new();
+    let mut right = PriceTick::new();
+    left.load(0);
+    right.load(0);
+    for &delta in &shared {
+        left.accumulate(delta);
+        right.accumulate(delta);
+    }
+    left.accumulate(0xAAAA);
+    left.accumulate(0xBBBB);
+    right.accumulate(0xCCCC);
+
+    let left_sketch = DeltaSketch::from_deltas(16, left.iter_history().copied());
+    let right_sketch = DeltaSketch::from_deltas(16, right.iter_history().copied());
+    let mut difference = left_sketch.diff(&right_sketch);
+    difference.sort_unstable();
+    assert_eq!(difference, vec![0xAAAA, 0xBBBB, 0xCCCC]);
+
+    assert!(left_sketch.diff(&left_sketch).is_empty());
+}
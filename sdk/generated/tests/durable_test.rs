@@ -0,0 +1,162 @@
+//! Integration tests for DurableDeltaState
+
+use std::cell::RefCell;
+use std::fs::OpenOptions;
+use std::io::{self, Read, Write};
+use std::rc::Rc;
+
+use atomik_video_streaming::{DeltaLog, DurableDeltaState, PriceTick};
+
+/// Contents and injected failures of an in-memory log
+#[derive(Default)]
+struct LogState {
+    bytes: Vec<u8>,
+    fail_write_after: Option<usize>,
+    fail_sync: bool,
+    fail_truncate: bool,
+}
+
+/// In-memory log whose writes, syncs and truncations can be made to fail
+struct FlakyLog {
+    state: Rc<RefCell<LogState>>,
+    read_pos: usize,
+}
+
+impl FlakyLog {
+    fn new(state: &Rc<RefCell<LogState>>) -> Self {
+        Self {
+            state: Rc::clone(state),
+            read_pos: 0,
+        }
+    }
+}
+
+impl Read for FlakyLog {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let state = self.state.borrow();
+        let n = buf.len().min(state.bytes.len() - self.read_pos);
+        buf[..n].copy_from_slice(&state.bytes[self.read_pos..self.read_pos + n]);
+        self.read_pos += n;
+        Ok(n)
+    }
+}
+
+impl Write for FlakyLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.borrow_mut();
+        let accepted = match state.fail_write_after {
+            // Accept a prefix of the record, then fail on the rest
+            Some(0) => {
+                state.fail_write_after = None;
+                return Err(io::Error::other("injected write failure"));
+            }
+            Some(n) => {
+                let n = n.min(buf.len());
+                state.fail_write_after = Some(0);
+                n
+            }
+            None => buf.len(),
+        };
+        state.bytes.extend_from_slice(&buf[..accepted]);
+        Ok(accepted)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl DeltaLog for FlakyLog {
+    fn sync_data(&mut self) -> io::Result<()> {
+        if std::mem::take(&mut self.state.borrow_mut().fail_sync) {
+            return Err(io::Error::other("injected sync failure"));
+        }
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: u64) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        if state.fail_truncate {
+            return Err(io::Error::other("injected truncate failure"));
+        }
+        state.bytes.truncate(len as usize);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_recovers_state_from_log() {
+    let path = std::env::temp_dir().join(format!("durable_delta_{}.wal", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let expected = {
+        let mut durable = DurableDeltaState::open(&path, PriceTick::from_state(0x1000)).unwrap();
+        for delta in [0x1, 0x20, 0x300] {
+            durable.accumulate(delta).unwrap();
+        }
+        durable.reconstruct()
+    };
+
+    // A crash mid-append leaves a torn record behind
+    OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .write_all(&[0xAB; 5])
+        .unwrap();
+
+    let mut recovered = DurableDeltaState::open(&path, PriceTick::from_state(0x1000)).unwrap();
+    assert_eq!(recovered.reconstruct(), expected);
+    assert_eq!(recovered.logged_count(), 3);
+    assert_eq!(recovered.inner().history_size(), 3);
+
+    recovered.accumulate(0x4000).unwrap();
+    drop(recovered);
+    let reopened = DurableDeltaState::open(&path, PriceTick::from_state(0x1000)).unwrap();
+    assert_eq!(reopened.reconstruct(), expected ^ 0x4000);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_failed_append_truncates_log() {
+    let state = Rc::new(RefCell::new(LogState::default()));
+    let mut durable =
+        DurableDeltaState::with_log(FlakyLog::new(&state), PriceTick::from_state(0x1000)).unwrap();
+    durable.accumulate(0x1).unwrap();
+
+    // A torn write and a failed fsync both leave the log at one record
+    state.borrow_mut().fail_write_after = Some(5);
+    assert!(durable.accumulate(0x20).is_err());
+    state.borrow_mut().fail_sync = true;
+    assert!(durable.accumulate(0x300).is_err());
+    assert_eq!(state.borrow().bytes.len(), 16);
+    assert_eq!(durable.logged_count(), 1);
+    assert_eq!(durable.reconstruct(), 0x1001);
+
+    durable.accumulate(0x4000).unwrap();
+    assert!(!durable.is_poisoned());
+
+    let reopened =
+        DurableDeltaState::with_log(FlakyLog::new(&state), PriceTick::from_state(0x1000)).unwrap();
+    assert_eq!(reopened.logged_count(), 2);
+    assert_eq!(reopened.reconstruct(), 0x5001);
+}
+
+#[test]
+fn test_failed_truncate_poisons_log() {
+    let state = Rc::new(RefCell::new(LogState::default()));
+    let mut durable =
+        DurableDeltaState::with_log(FlakyLog::new(&state), PriceTick::from_state(0)).unwrap();
+    {
+        let mut state = state.borrow_mut();
+        state.fail_sync = true;
+        state.fail_truncate = true;
+    }
+    assert!(durable.accumulate(0x1).is_err());
+    assert!(durable.is_poisoned());
+
+    state.borrow_mut().fail_truncate = false;
+    assert!(durable.accumulate(0x2).is_err());
+    assert_eq!(durable.reconstruct(), 0);
+    assert_eq!(durable.logged_count(), 0);
+}
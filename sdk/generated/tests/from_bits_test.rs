@@ -0,0 +1,24 @@
+//! Integration tests for the provided FromBits conversions
+
+use atomik_video_streaming::{H264Delta, PriceTick};
+
+#[test]
+fn test_reconstruct_as_primitives() {
+    let bits = (-1.5f64).to_bits();
+    let manager = PriceTick::from_state(bits);
+
+    assert_eq!(manager.reconstruct_as::<u64>(), bits);
+    assert_eq!(manager.reconstruct_as::<i64>(), bits as i64);
+    assert!(manager.reconstruct_as::<i64>() < 0);
+    assert_eq!(manager.reconstruct_as::<f64>(), -1.5);
+    assert_eq!(manager.reconstruct_as::<[u8; 8]>(), bits.to_le_bytes());
+}
+
+#[test]
+fn test_reconstruct_as_wide_primitives() {
+    let mut manager = H264Delta::from_state(u128::MAX);
+    manager.accumulate(1);
+
+    assert_eq!(manager.reconstruct_as::<i128>(), -2);
+    assert_eq!(manager.reconstruct_as::<[u8; 16]>()[0], 0xFE);
+}
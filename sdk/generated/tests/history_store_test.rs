@@ -0,0 +1,68 @@
+//! Integration tests for pluggable history storage
+
+use atomik_video_streaming::{H264Delta, HistoryStore, PriceTick};
+
+/// Minimal store backed by a plain Vec
+#[derive(Debug, Default)]
+struct VecStore<T> {
+    deltas: Vec<T>,
+}
+
+impl<T> HistoryStore<T> for VecStore<T> {
+    type Iter<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn push(&mut self, delta: T) {
+        self.deltas.push(delta);
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.deltas.pop()
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        if self.deltas.is_empty() {
+            None
+        } else {
+            Some(self.deltas.remove(0))
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.deltas.iter()
+    }
+}
+
+#[test]
+fn test_custom_store() {
+    let mut manager = PriceTick::with_store(VecStore::default());
+    manager.load(0xAAAA);
+    manager.accumulate(0x1);
+    manager.accumulate(0x2);
+    manager.accumulate(0x4);
+    assert_eq!(manager.history_size(), 3);
+    assert_eq!(manager.reconstruct(), 0xAAAD);
+
+    // Removal uses the store's provided remove()
+    assert!(manager.remove_delta(0x2));
+    assert_eq!(manager.rollback_collect(2), vec![0x4, 0x1]);
+    assert_eq!(manager.reconstruct(), 0xAAAA);
+}
+
+#[test]
+fn test_custom_store_eviction() {
+    let mut manager = H264Delta::with_store(VecStore::default());
+    let capacity = manager.max_history();
+    for delta in 0..capacity as u128 + 10 {
+        manager.accumulate(delta);
+    }
+    assert_eq!(manager.history_size(), capacity);
+    let states: Vec<_> = manager.iter_states().collect();
+    assert_eq!(states.last().unwrap().1, manager.reconstruct());
+}
@@ -0,0 +1,34 @@
+//! Integration tests for MonotonicDeltaState
+
+use atomik_video_streaming::{DeltaError, MonotonicDeltaState, PriceTick};
+
+#[test]
+fn test_rejects_decreasing_delta() {
+    let mut counter = MonotonicDeltaState::new(PriceTick::new());
+    counter.load(5);
+
+    // 5 ^ 3 = 6: increases
+    counter.accumulate(3).unwrap();
+    assert_eq!(counter.reconstruct(), 6);
+
+    // 6 ^ 4 = 2: decreases, rejected with state unchanged
+    assert_eq!(counter.accumulate(4), Err(DeltaError::NonMonotonic));
+    assert_eq!(counter.reconstruct(), 6);
+    assert_eq!(counter.inner().history_size(), 1);
+
+    // Zero delta keeps the state equal, which is allowed
+    counter.accumulate(0).unwrap();
+    assert_eq!(counter.into_inner().history_size(), 2);
+}
+
+#[test]
+fn test_passes_through_inner_errors() {
+    let mut unloaded = MonotonicDeltaState::new(PriceTick::builder().strict_load(true).build());
+    assert_eq!(unloaded.accumulate(1), Err(DeltaError::NotLoaded));
+
+    let mut inner = PriceTick::from_state(5);
+    inner.freeze();
+    let mut frozen = MonotonicDeltaState::new(inner);
+    assert_eq!(frozen.accumulate(3), Err(DeltaError::Frozen));
+    assert_eq!(frozen.reconstruct(), 5);
+}
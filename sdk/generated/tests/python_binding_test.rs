@@ -0,0 +1,29 @@
+//! Integration tests for the PriceTick Python binding
+
+#![cfg(feature = "python")]
+
+use atomik_video_streaming::finance::trading::python::PyPriceTick;
+use pyo3::prelude::*;
+
+#[test]
+fn test_pyclass_methods() {
+    Python::initialize();
+    Python::attach(|py| {
+        let tick = Bound::new(py, PyPriceTick::new()).unwrap();
+        tick.call_method1("load", (0xAAAAAAAAAAAAAAAAu64,)).unwrap();
+        tick.call_method1("accumulate", (0x5555555555555555u64,)).unwrap();
+
+        let state: u64 = tick.call_method0("reconstruct").unwrap().extract().unwrap();
+        assert_eq!(state, 0xFFFFFFFFFFFFFFFF);
+        let size: usize = tick.call_method0("history_size").unwrap().extract().unwrap();
+        assert_eq!(size, 1);
+
+        let rolled: usize = tick.call_method1("rollback", (1,)).unwrap().extract().unwrap();
+        assert_eq!(rolled, 1);
+        let state: u64 = tick.call_method0("reconstruct").unwrap().extract().unwrap();
+        assert_eq!(state, 0xAAAAAAAAAAAAAAAA);
+
+        // Negative ints cannot convert to u64
+        assert!(tick.call_method1("accumulate", (-1,)).is_err());
+    });
+}
@@ -0,0 +1,46 @@
+//! Integration tests for RateLimitedDeltaState
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use atomik_video_streaming::{Clock, PriceTick, RateLimitedDeltaState};
+
+#[derive(Clone)]
+struct MockClock(Rc<Cell<Instant>>);
+
+impl MockClock {
+    fn advance(&self, by: Duration) {
+        self.0.set(self.0.get() + by);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
+
+#[test]
+fn test_coalesces_within_interval() {
+    let clock = MockClock(Rc::new(Cell::new(Instant::now())));
+    let mut manager = PriceTick::new();
+    manager.load(0x1000);
+    let mut limited = RateLimitedDeltaState::with_clock(manager, Duration::from_millis(10), clock.clone());
+
+    for delta in [0x1, 0x2, 0x4] {
+        assert!(!limited.push(delta));
+        clock.advance(Duration::from_millis(2));
+    }
+    assert_eq!(limited.inner().history_size(), 0);
+
+    clock.advance(Duration::from_millis(10));
+    assert!(limited.push(0x8));
+    assert_eq!(limited.inner().history_size(), 1);
+    assert_eq!(limited.inner().reconstruct(), 0x1000 ^ 0xF);
+
+    assert!(!limited.push(0x10));
+    assert!(limited.flush());
+    assert!(!limited.flush());
+    assert_eq!(limited.into_inner().history_size(), 2);
+}
@@ -0,0 +1,22 @@
+//! Integration tests for TickBatcher
+
+use atomik_video_streaming::{PriceTick, TickBatcher};
+
+#[test]
+fn test_flush_combines_tick_deltas() {
+    let mut manager = PriceTick::new();
+    manager.load(0x1000);
+    let mut batcher = TickBatcher::new(manager);
+
+    for delta in [0x01, 0x0F, 0x30, 0x01] {
+        batcher.push(delta);
+    }
+    assert_eq!(batcher.pending_count(), 4);
+    assert_eq!(batcher.flush_tick(), 0x01 ^ 0x0F ^ 0x30 ^ 0x01);
+    assert_eq!(batcher.inner().history_size(), 1);
+    assert_eq!(batcher.inner().reconstruct(), 0x1000 ^ 0x3F);
+
+    // An empty tick sends nothing and records nothing
+    assert_eq!(batcher.flush_tick(), 0);
+    assert_eq!(batcher.into_inner().history_size(), 1);
+}
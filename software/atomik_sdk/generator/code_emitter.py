@@ -8,11 +8,15 @@ from __future__ import annotations
 
 from abc import ABC, abstractmethod
 from dataclasses import dataclass
+from fnmatch import fnmatch
 from pathlib import Path
 from typing import Any
 
 from .namespace_mapper import NamespaceMapping
 
+# Marker file listing output paths that are maintained by hand
+HAND_MAINTAINED_FILE = "HAND_MAINTAINED"
+
 
 @dataclass
 class GeneratedFile:
@@ -254,6 +258,10 @@ class MultiLanguageEmitter:
         """
         Write generated files to disk.
 
+        Files matching a glob pattern listed in the output directory's
+        HAND_MAINTAINED marker are never overwritten; each skipped file is
+        reported as a warning on its GenerationResult.
+
         Args:
             results: Dictionary of GenerationResults by language
             output_dir: Output directory root
@@ -263,6 +271,7 @@ class MultiLanguageEmitter:
         """
         output_path = Path(output_dir)
         output_path.mkdir(parents=True, exist_ok=True)
+        hand_maintained = self._load_hand_maintained(output_path)
 
         written_files = []
 
@@ -273,6 +282,12 @@ class MultiLanguageEmitter:
             for gen_file in result.files:
                 file_path = output_path / gen_file.relative_path
 
+                if any(fnmatch(gen_file.relative_path, pattern) for pattern in hand_maintained):
+                    result.warnings.append(
+                        f"Skipped hand-maintained file: {gen_file.relative_path}"
+                    )
+                    continue
+
                 # Create parent directories
                 file_path.parent.mkdir(parents=True, exist_ok=True)
 
@@ -283,3 +298,17 @@ class MultiLanguageEmitter:
                 written_files.append(str(file_path))
 
         return written_files
+
+    @staticmethod
+    def _load_hand_maintained(output_path: Path) -> list[str]:
+        """Read glob patterns from the HAND_MAINTAINED marker, if present."""
+        marker = output_path / HAND_MAINTAINED_FILE
+        if not marker.exists():
+            return []
+
+        patterns = []
+        for line in marker.read_text(encoding='utf-8').splitlines():
+            line = line.strip()
+            if line and not line.startswith('#'):
+                patterns.append(line)
+        return patterns
@@ -438,6 +438,27 @@ class TestCodeEmitter:
         assert results['lang1'].success is True
         assert results['lang2'].success is True
 
+    def test_write_skips_hand_maintained_files(self, tmp_path):
+        """Test that HAND_MAINTAINED patterns are never overwritten."""
+        (tmp_path / "HAND_MAINTAINED").write_text("# kept by hand\nsrc/*.rs\n")
+        (tmp_path / "src").mkdir()
+        (tmp_path / "src" / "lib.rs").write_text("// edited by hand")
+
+        result = GenerationResult(
+            success=True,
+            files=[
+                GeneratedFile("src/lib.rs", "// generated", "rust", "Entry point"),
+                GeneratedFile("src/app.js", "// generated", "javascript", "Module"),
+            ],
+            errors=[],
+            warnings=[]
+        )
+        written = MultiLanguageEmitter().write_generated_files({'mixed': result}, tmp_path)
+
+        assert written == [str(tmp_path / "src" / "app.js")]
+        assert (tmp_path / "src" / "lib.rs").read_text() == "// edited by hand"
+        assert result.warnings == ["Skipped hand-maintained file: src/lib.rs"]
+
 
 # Generator Engine Tests
 